@@ -0,0 +1,345 @@
+use crate::opts;
+use std::cmp::Ordering;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Counts {
+    increases: u32,
+    decreases: u32,
+    unchanged: u32,
+}
+
+// The last `size` depths. Consecutive window sums differ only by the depth
+// entering and the depth leaving the window, so comparing those two is
+// enough to compare the sums.
+struct Window {
+    buf: Vec<u32>,
+    next: usize,
+    filled: usize,
+}
+
+impl Window {
+    fn with_size(size: usize) -> Window {
+        assert!(size > 0, "window size must be positive");
+        Window {
+            buf: vec![0; size],
+            next: 0,
+            filled: 0,
+        }
+    }
+
+    // Returns the depth that dropped out of the window, if it was full
+    fn push(&mut self, depth: u32) -> Option<u32> {
+        let old = self.buf[self.next];
+        self.buf[self.next] = depth;
+        self.next = (self.next + 1) % self.buf.len();
+
+        if self.filled < self.buf.len() {
+            self.filled += 1;
+            None
+        } else {
+            Some(old)
+        }
+    }
+}
+
+// Depths along with the line numbers they came from
+fn load_lines(input: &mut dyn Read) -> impl Iterator<Item = (usize, u32)> + '_ {
+    BufReader::new(input)
+        .lines()
+        .map(|l| l.unwrap())
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(n, l)| (n + 1, l.parse().unwrap()))
+}
+
+fn load(input: &mut dyn Read) -> impl Iterator<Item = u32> + '_ {
+    load_lines(input).map(|(_, depth)| depth)
+}
+
+fn compare_windows(depths: impl Iterator<Item = u32>, size: usize) -> Counts {
+    let mut window = Window::with_size(size);
+    let mut counts = Counts::default();
+
+    for depth in depths {
+        let leaving = match window.push(depth) {
+            Some(d) => d,
+            None => continue,
+        };
+
+        match depth.cmp(&leaving) {
+            Ordering::Greater => counts.increases += 1,
+            Ordering::Less => counts.decreases += 1,
+            Ordering::Equal => counts.unchanged += 1,
+        }
+    }
+
+    counts
+}
+
+fn find_counts(input: &mut dyn Read, size: usize) -> Counts {
+    compare_windows(load(input), size)
+}
+
+fn run_with_window(input: &mut dyn Read, args: &[String], default_size: usize) {
+    let size = opts::value_or(args, "--window", default_size);
+    if size == 0 {
+        opts::fail("usage: --window N, where N is at least 1");
+    }
+
+    let Counts {
+        increases,
+        decreases,
+        unchanged,
+    } = find_counts(input, size);
+
+    println!(
+        "{} (decreases: {}, unchanged: {})",
+        increases, decreases, unchanged
+    );
+}
+
+// First and last line of a run of consecutive changes in one direction
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Run {
+    first_line: usize,
+    last_line: usize,
+}
+
+impl Run {
+    fn steps(&self) -> usize {
+        self.last_line - self.first_line
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Jump {
+    line: usize,
+    change: i64,
+}
+
+#[derive(Debug, PartialEq)]
+struct Report {
+    min: u32,
+    max: u32,
+    mean: f64,
+    longest_increase: Option<Run>,
+    longest_decrease: Option<Run>,
+    largest_jump: Option<Jump>,
+    sparkline: String,
+}
+
+const SPARK_LEVELS: &[u8] = b"_.-:=+*#%@";
+const SPARK_WIDTH: usize = 72;
+
+// Averages the depths into at most `width` buckets and draws each as one
+// character, shallowest as the first level and deepest as the last.
+fn sparkline(depths: &[u32], width: usize) -> String {
+    let min = *depths.iter().min().unwrap() as f64;
+    let max = *depths.iter().max().unwrap() as f64;
+    let bucket = depths.len().div_ceil(width);
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+
+    depths
+        .chunks(bucket)
+        .map(|c| {
+            let avg = c.iter().map(|d| *d as f64).sum::<f64>() / c.len() as f64;
+            let level = if max > min {
+                ((avg - min) / (max - min) * top).round() as usize
+            } else {
+                0
+            };
+            SPARK_LEVELS[level] as char
+        })
+        .collect()
+}
+
+fn longest_run(lines: &[(usize, u32)], cmp: Ordering) -> Option<Run> {
+    let mut best: Option<Run> = None;
+    let mut current: Option<Run> = None;
+
+    for pair in lines.windows(2) {
+        let ((l0, d0), (l1, d1)) = (pair[0], pair[1]);
+        current = if d1.cmp(&d0) == cmp {
+            match current {
+                Some(run) => Some(Run {
+                    last_line: l1,
+                    ..run
+                }),
+                None => Some(Run {
+                    first_line: l0,
+                    last_line: l1,
+                }),
+            }
+        } else {
+            None
+        };
+
+        if let Some(run) = current {
+            if best.is_none_or(|b| run.steps() > b.steps()) {
+                best = current;
+            }
+        }
+    }
+
+    best
+}
+
+fn largest_jump(lines: &[(usize, u32)]) -> Option<Jump> {
+    lines
+        .windows(2)
+        .map(|pair| Jump {
+            line: pair[1].0,
+            change: pair[1].1 as i64 - pair[0].1 as i64,
+        })
+        .max_by_key(|j| j.change.abs())
+}
+
+fn report(input: &mut dyn Read) -> Option<Report> {
+    let lines: Vec<(usize, u32)> = load_lines(input).collect();
+    let depths: Vec<u32> = lines.iter().map(|(_, d)| *d).collect();
+    if depths.is_empty() {
+        return None;
+    }
+
+    Some(Report {
+        min: *depths.iter().min().unwrap(),
+        max: *depths.iter().max().unwrap(),
+        mean: depths.iter().map(|d| *d as f64).sum::<f64>() / depths.len() as f64,
+        longest_increase: longest_run(&lines, Ordering::Greater),
+        longest_decrease: longest_run(&lines, Ordering::Less),
+        largest_jump: largest_jump(&lines),
+        sparkline: sparkline(&depths, SPARK_WIDTH),
+    })
+}
+
+fn run_text(run: Option<Run>) -> String {
+    match run {
+        Some(r) => format!(
+            "{} steps, lines {}-{}",
+            r.steps(),
+            r.first_line,
+            r.last_line
+        ),
+        None => "none".to_string(),
+    }
+}
+
+pub fn run_report(input: &mut dyn Read) {
+    let r = match report(input) {
+        Some(r) => r,
+        None => {
+            println!("no depths");
+            return;
+        }
+    };
+
+    println!("min depth: {}", r.min);
+    println!("max depth: {}", r.max);
+    println!("mean depth: {:.2}", r.mean);
+    println!("longest increase: {}", run_text(r.longest_increase));
+    println!("longest decrease: {}", run_text(r.longest_decrease));
+    match r.largest_jump {
+        Some(j) => println!("largest jump: {:+} at line {}", j.change, j.line),
+        None => println!("largest jump: none"),
+    }
+    println!("profile: {}", r.sparkline);
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    run_with_window(input, args, 1);
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    run_with_window(input, args, 3);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_sample() {
+        let mut f = File::open("input/day-1-sample.txt").unwrap();
+        assert_eq!(
+            find_counts(&mut f, 1),
+            Counts {
+                increases: 7,
+                decreases: 2,
+                unchanged: 0
+            }
+        );
+
+        let mut f = File::open("input/day-1-sample.txt").unwrap();
+        assert_eq!(
+            find_counts(&mut f, 3),
+            Counts {
+                increases: 5,
+                decreases: 1,
+                unchanged: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_short_input() {
+        let counts = compare_windows([1, 2, 3].into_iter(), 3);
+        assert_eq!(counts, Counts::default());
+        assert_eq!(compare_windows([].into_iter(), 1), Counts::default());
+    }
+
+    #[test]
+    fn test_report_sample() {
+        let mut f = File::open("input/day-1-sample.txt").unwrap();
+        let r = report(&mut f).unwrap();
+        assert_eq!(r.min, 199);
+        assert_eq!(r.max, 269);
+        assert_eq!(r.mean, 2256.0 / 10.0);
+        assert_eq!(
+            r.longest_increase,
+            Some(Run {
+                first_line: 1,
+                last_line: 4
+            })
+        );
+        assert_eq!(
+            r.longest_decrease,
+            Some(Run {
+                first_line: 4,
+                last_line: 5
+            })
+        );
+        assert_eq!(
+            r.largest_jump,
+            Some(Jump {
+                line: 7,
+                change: 33
+            })
+        );
+        assert_eq!(r.sparkline.len(), 10);
+        assert_eq!(r.sparkline, "__.._.+@%%");
+    }
+
+    #[test]
+    fn test_report_empty() {
+        assert_eq!(report(&mut "".as_bytes()), None);
+        let r = report(&mut "5\n".as_bytes()).unwrap();
+        assert_eq!((r.min, r.max, r.longest_increase), (5, 5, None));
+        assert_eq!(r.sparkline, "_");
+    }
+
+    #[test]
+    fn test_part1_full() {
+        let mut f = File::open("input/day-1.txt").unwrap();
+        assert_eq!(find_counts(&mut f, 1).increases, 1529);
+    }
+
+    #[test]
+    fn test_part2_full() {
+        let mut f = File::open("input/day-1.txt").unwrap();
+        assert_eq!(find_counts(&mut f, 3).increases, 1567);
+    }
+}
@@ -0,0 +1,626 @@
+use crate::opts;
+use std::fmt;
+use std::fmt::Debug;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Position {
+    depth: i64,
+    distance: i64,
+}
+
+impl Position {
+    fn prod(&self) -> Result<i64, Problem> {
+        checked(self.depth.checked_mul(self.distance))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Problem {
+    Surfaced,
+    Overflow,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Surfaced => write!(f, "the submarine would surface"),
+            Problem::Overflow => write!(f, "the position would overflow"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Diagnostic {
+    line: usize,
+    problem: Problem,
+}
+
+// Adds `change` to `depth` without overflowing or going above the
+// surface. With `clamp`, the surface is a hard floor instead of an error.
+fn dive(depth: i64, change: i64, clamp: bool) -> Result<i64, Problem> {
+    match depth.checked_add(change) {
+        None => Err(Problem::Overflow),
+        Some(d) if d >= 0 => Ok(d),
+        Some(_) if clamp => Ok(0),
+        Some(_) => Err(Problem::Surfaced),
+    }
+}
+
+fn checked(res: Option<i64>) -> Result<i64, Problem> {
+    res.ok_or(Problem::Overflow)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Cmd {
+    Fwd(i64),
+    Down(i64),
+    Up(i64),
+    Back(i64),
+}
+
+impl Cmd {
+    fn from_line(line: &str) -> Result<Cmd, String> {
+        let (name, num) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("expected a command and a number, got {:?}", line))?;
+        let n = num
+            .trim()
+            .parse()
+            .ok()
+            .filter(|n| *n >= 0)
+            .ok_or_else(|| format!("bad number {:?}", num))?;
+
+        match name {
+            "forward" => Ok(Cmd::Fwd(n)),
+            "down" => Ok(Cmd::Down(n)),
+            "up" => Ok(Cmd::Up(n)),
+            "back" => Ok(Cmd::Back(n)),
+            _ => Err(format!("unknown command {:?}", name)),
+        }
+    }
+}
+
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cmd::Fwd(n) => write!(f, "forward {}", n),
+            Cmd::Down(n) => write!(f, "down {}", n),
+            Cmd::Up(n) => write!(f, "up {}", n),
+            Cmd::Back(n) => write!(f, "back {}", n),
+        }
+    }
+}
+
+// How a submarine interprets the commands of the planned course
+trait Submarine: Debug + Default {
+    fn apply(&mut self, cmd: &Cmd, clamp: bool) -> Result<(), Problem>;
+    fn position(&self) -> &Position;
+
+    fn aim(&self) -> i64 {
+        0
+    }
+}
+
+// Part 1: up and down change the depth directly
+#[derive(Debug, Default)]
+struct Direct {
+    pos: Position,
+}
+
+impl Submarine for Direct {
+    fn apply(&mut self, cmd: &Cmd, clamp: bool) -> Result<(), Problem> {
+        match *cmd {
+            Cmd::Fwd(n) => self.pos.distance = checked(self.pos.distance.checked_add(n))?,
+            Cmd::Down(n) => self.pos.depth = dive(self.pos.depth, n, clamp)?,
+            Cmd::Up(n) => self.pos.depth = dive(self.pos.depth, -n, clamp)?,
+            Cmd::Back(n) => self.pos.distance = checked(self.pos.distance.checked_sub(n))?,
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> &Position {
+        &self.pos
+    }
+}
+
+// Part 2: up and down turn the submarine, forward follows the aim
+#[derive(Debug, Default)]
+struct Aim {
+    aim: i64,
+    pos: Position,
+}
+
+impl Submarine for Aim {
+    fn apply(&mut self, cmd: &Cmd, clamp: bool) -> Result<(), Problem> {
+        match *cmd {
+            Cmd::Fwd(n) => {
+                self.pos.distance = checked(self.pos.distance.checked_add(n))?;
+                let change = checked(n.checked_mul(self.aim))?;
+                self.pos.depth = dive(self.pos.depth, change, clamp)?;
+            }
+            Cmd::Back(n) => {
+                self.pos.distance = checked(self.pos.distance.checked_sub(n))?;
+                let change = checked(n.checked_mul(self.aim))?;
+                self.pos.depth = dive(self.pos.depth, -change, clamp)?;
+            }
+            Cmd::Down(n) => self.aim = checked(self.aim.checked_add(n))?,
+            Cmd::Up(n) => self.aim = checked(self.aim.checked_sub(n))?,
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> &Position {
+        &self.pos
+    }
+
+    fn aim(&self) -> i64 {
+        self.aim
+    }
+}
+
+// A planned course: commands with the line numbers they came from, and
+// blocks of them to repeat
+#[derive(Debug, Eq, PartialEq)]
+enum Step {
+    Cmd(usize, Cmd),
+    Repeat(u32, Vec<Step>),
+}
+
+fn parse_course(lines: impl Iterator<Item = String>) -> Result<Vec<Step>, (usize, String)> {
+    // The course so far, and the repeat blocks still open around it
+    let mut open: Vec<(usize, u32, Vec<Step>)> = vec![(0, 1, Vec::new())];
+
+    for (n, raw) in lines.enumerate() {
+        let line_no = n + 1;
+        let line = raw.split('#').next().unwrap().trim();
+
+        if line.is_empty() {
+            continue;
+        } else if line == "}" {
+            if open.len() == 1 {
+                return Err((line_no, "unexpected }".to_string()));
+            }
+            let (_, count, steps) = open.pop().unwrap();
+            open.last_mut().unwrap().2.push(Step::Repeat(count, steps));
+        } else if let Some(rest) = line.strip_prefix("repeat ") {
+            let count = rest
+                .strip_suffix('{')
+                .and_then(|c| c.trim().parse().ok())
+                .ok_or_else(|| (line_no, format!("expected repeat K {{, got {:?}", line)))?;
+            open.push((line_no, count, Vec::new()));
+        } else {
+            let cmd = Cmd::from_line(line).map_err(|e| (line_no, e))?;
+            open.last_mut().unwrap().2.push(Step::Cmd(line_no, cmd));
+        }
+    }
+
+    if open.len() > 1 {
+        let (line_no, _, _) = open.pop().unwrap();
+        return Err((line_no, "repeat block is never closed".to_string()));
+    }
+
+    Ok(open.pop().unwrap().2)
+}
+
+fn load(input: &mut dyn Read) -> Vec<Step> {
+    match parse_course(BufReader::new(input).lines().map(|l| l.unwrap())) {
+        Ok(course) => course,
+        Err((line, e)) => panic!("line {}: {}", line, e),
+    }
+}
+
+fn follow<S: Submarine>(
+    sub: &mut S,
+    course: &[Step],
+    clamp: bool,
+    visit: &mut impl FnMut(&Cmd, &S),
+) -> Result<(), Diagnostic> {
+    for step in course {
+        match step {
+            Step::Cmd(line, cmd) => {
+                sub.apply(cmd, clamp).map_err(|problem| Diagnostic {
+                    line: *line,
+                    problem,
+                })?;
+                visit(cmd, sub);
+            }
+            Step::Repeat(count, steps) => {
+                for _ in 0..*count {
+                    follow(sub, steps, clamp, visit)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Runs the course, calling `visit` with the submarine after each command
+fn navigate_with<S: Submarine>(
+    input: &mut dyn Read,
+    clamp: bool,
+    mut visit: impl FnMut(&Cmd, &S),
+) -> Result<S, Diagnostic> {
+    let mut sub = S::default();
+    follow(&mut sub, &load(input), clamp, &mut visit)?;
+    Ok(sub)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct TracePoint {
+    cmd: String,
+    aim: i64,
+    depth: i64,
+    distance: i64,
+}
+
+fn trace_point<S: Submarine>(cmd: &Cmd, sub: &S) -> TracePoint {
+    TracePoint {
+        cmd: cmd.to_string(),
+        aim: sub.aim(),
+        depth: sub.position().depth,
+        distance: sub.position().distance,
+    }
+}
+
+fn write_csv(out: &mut dyn Write, trace: &[TracePoint]) -> std::io::Result<()> {
+    writeln!(out, "step,command,aim,depth,distance")?;
+    for (n, p) in trace.iter().enumerate() {
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            n + 1,
+            p.cmd,
+            p.aim,
+            p.depth,
+            p.distance
+        )?;
+    }
+    Ok(())
+}
+
+// Plots depth over distance. The view box is the extent of the course,
+// starting point included, so depth grows downwards just like SVG
+// coordinates do.
+fn write_svg(out: &mut dyn Write, trace: &[TracePoint]) -> std::io::Result<()> {
+    let extent = |coord: fn(&TracePoint) -> i64| {
+        let min = trace.iter().map(coord).min().unwrap_or(0).min(0);
+        let max = trace.iter().map(coord).max().unwrap_or(0).max(0);
+        (min, (max - min).max(1))
+    };
+    let (x, width) = extent(|p| p.distance);
+    let (y, height) = extent(|p| p.depth);
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="400" viewBox="{} {} {} {}" preserveAspectRatio="none">"#,
+        x, y, width, height
+    )?;
+    write!(
+        out,
+        r#"  <polyline fill="none" stroke="navy" stroke-width="2" vector-effect="non-scaling-stroke" points="0,0"#
+    )?;
+    for p in trace.iter() {
+        write!(out, " {},{}", p.distance, p.depth)?;
+    }
+    writeln!(out, r#""/>"#)?;
+    writeln!(out, "</svg>")
+}
+
+fn export(
+    path: &str,
+    trace: &[TracePoint],
+    write: fn(&mut dyn Write, &[TracePoint]) -> std::io::Result<()>,
+) {
+    let res = std::fs::File::create(path).and_then(|mut f| write(&mut f, trace));
+    if let Err(e) = res {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    }
+}
+
+fn run_with<S: Submarine>(input: &mut dyn Read, args: &[String]) {
+    let csv = opts::value::<String>(args, "--csv");
+    let svg = opts::value::<String>(args, "--svg");
+
+    // Repeated blocks can make for a very long course, so only keep the
+    // trace when something is going to be written out of it
+    let record = csv.is_some() || svg.is_some();
+    let mut trace = Vec::new();
+    let res = navigate_with(input, opts::flag(args, "--clamp"), |cmd, sub: &S| {
+        if record {
+            trace.push(trace_point(cmd, sub))
+        }
+    });
+
+    if let Some(path) = csv {
+        export(&path, &trace, write_csv);
+    }
+    if let Some(path) = svg {
+        export(&path, &trace, write_svg);
+    }
+
+    match res {
+        Ok(sub) => match sub.position().prod() {
+            Ok(answer) => println!("{:?} => answer: {}", sub, answer),
+            Err(problem) => {
+                eprintln!("{:?} => answer: {}", sub, problem);
+                std::process::exit(1);
+            }
+        },
+        Err(Diagnostic { line, problem }) => {
+            eprintln!("line {}: {}", line, problem);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    run_with::<Direct>(input, args);
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    run_with::<Aim>(input, args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn navigate<S: Submarine>(input: &mut dyn Read, clamp: bool) -> Result<S, Diagnostic> {
+        navigate_with(input, clamp, |_, _: &S| {})
+    }
+
+    #[test]
+    fn test_cmd() {
+        assert_eq!(Ok(Cmd::Fwd(4)), Cmd::from_line("forward 4"));
+        assert_eq!(Ok(Cmd::Up(7)), Cmd::from_line("up 7"));
+        assert_eq!(Ok(Cmd::Down(1)), Cmd::from_line("down 1"));
+        assert!(Cmd::from_line("sideways 1").is_err());
+        assert!(Cmd::from_line("up").is_err());
+        assert!(Cmd::from_line("up x").is_err());
+        assert!(Cmd::from_line("up -3").is_err());
+        assert_eq!(Ok(Cmd::Back(2)), Cmd::from_line("back 2"));
+    }
+
+    const EXTENDED: &str = "# A course with every kind of step
+forward 4
+
+repeat 2 {
+    down 3  # dive
+    repeat 3 {
+        forward 1
+    }
+}
+back 2
+up 1
+";
+
+    fn course(txt: &str) -> Result<Vec<Step>, (usize, String)> {
+        parse_course(txt.lines().map(|l| l.to_string()))
+    }
+
+    #[test]
+    fn test_parse_course() {
+        assert_eq!(
+            course(EXTENDED).unwrap(),
+            vec![
+                Step::Cmd(2, Cmd::Fwd(4)),
+                Step::Repeat(
+                    2,
+                    vec![
+                        Step::Cmd(5, Cmd::Down(3)),
+                        Step::Repeat(3, vec![Step::Cmd(7, Cmd::Fwd(1))])
+                    ]
+                ),
+                Step::Cmd(10, Cmd::Back(2)),
+                Step::Cmd(11, Cmd::Up(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(course("up 1\n}").unwrap_err().0, 2);
+        assert_eq!(course("repeat 2 {\nup 1\n").unwrap_err().0, 1);
+        assert_eq!(course("repeat x {\n}").unwrap_err().0, 1);
+        assert_eq!(course("repeat 2\n").unwrap_err().0, 1);
+        assert_eq!(course("\n\nleft 2").unwrap_err().0, 3);
+    }
+
+    #[test]
+    fn test_extended_course() {
+        let sub: Direct = navigate(&mut EXTENDED.as_bytes(), false).unwrap();
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 5,
+                distance: 8
+            }
+        );
+
+        let sub: Aim = navigate(&mut EXTENDED.as_bytes(), false).unwrap();
+        assert_eq!(sub.aim, 5);
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 3 * 3 + 6 * 3 - 2 * 6,
+                distance: 8
+            }
+        );
+    }
+
+    #[test]
+    fn test_surfacing() {
+        let course = "down 2\nforward 3\nup 5\nforward 1\n";
+        let res = navigate::<Direct>(&mut course.as_bytes(), false);
+        assert_eq!(
+            res.unwrap_err(),
+            Diagnostic {
+                line: 3,
+                problem: Problem::Surfaced
+            }
+        );
+
+        let sub: Direct = navigate(&mut course.as_bytes(), true).unwrap();
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 0,
+                distance: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_aim_surfacing() {
+        // Aiming upwards is fine until moving forward takes us above the surface
+        let course = "forward 2\ndown 1\nforward 2\nup 3\nforward 1\nforward 1\n";
+        let res = navigate::<Aim>(&mut course.as_bytes(), false);
+        assert_eq!(
+            res.unwrap_err(),
+            Diagnostic {
+                line: 6,
+                problem: Problem::Surfaced
+            }
+        );
+
+        let sub: Aim = navigate(&mut course.as_bytes(), true).unwrap();
+        assert_eq!(sub.aim, -2);
+        assert_eq!(sub.pos.depth, 0);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let course = format!("down {}\nforward 2\n", i64::MAX);
+        let res = navigate::<Aim>(&mut course.as_bytes(), false);
+        assert_eq!(
+            res.unwrap_err(),
+            Diagnostic {
+                line: 2,
+                problem: Problem::Overflow
+            }
+        );
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        let course = "down 10000000000\nforward 10000000000\n";
+        let sub: Direct = navigate(&mut course.as_bytes(), false).unwrap();
+        assert_eq!(sub.pos.prod(), Err(Problem::Overflow));
+        let course = "down 1\nforward 10000000000\n";
+        let sub: Aim = navigate(&mut course.as_bytes(), false).unwrap();
+        assert_eq!(sub.pos.prod(), Err(Problem::Overflow));
+    }
+
+    fn trace<S: Submarine>(input: &mut dyn Read) -> Vec<TracePoint> {
+        let mut trace = Vec::new();
+        navigate_with(input, false, |cmd, sub: &S| {
+            trace.push(trace_point(cmd, sub))
+        })
+        .unwrap();
+        trace
+    }
+
+    fn sample_trace<S: Submarine>() -> Vec<TracePoint> {
+        trace::<S>(&mut File::open("input/day-2-sample.txt").unwrap())
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &sample_trace::<Aim>()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "step,command,aim,depth,distance");
+        assert_eq!(lines[3], "3,forward 8,5,40,13");
+        assert_eq!(lines[6], "6,forward 2,10,60,15");
+
+        let mut out = Vec::new();
+        write_csv(&mut out, &sample_trace::<Direct>()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().nth(4), Some("4,up 3,0,2,13"));
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut out = Vec::new();
+        write_svg(&mut out, &sample_trace::<Direct>()).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("<svg "));
+        assert!(text.contains(r#"viewBox="0 0 15 10""#));
+        assert!(text.contains(r#"points="0,0 5,0 5,5 13,5 13,2 13,10 15,10""#));
+        assert!(text.trim_end().ends_with("</svg>"));
+
+        // Going back puts the course left of where it started
+        let mut out = Vec::new();
+        let course = "back 5\ndown 3\nforward 2\n";
+        write_svg(&mut out, &trace::<Direct>(&mut course.as_bytes())).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(r#"viewBox="-5 0 5 3""#));
+        assert!(text.contains(r#"points="0,0 -5,0 -5,3 -3,3""#));
+    }
+
+    #[test]
+    fn test_direct_example() {
+        let mut f = File::open("input/day-2-sample.txt").unwrap();
+        let sub: Direct = navigate(&mut f, false).unwrap();
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 10,
+                distance: 15
+            }
+        );
+        assert_eq!(sub.pos.prod(), Ok(150));
+    }
+
+    #[test]
+    fn test_direct_full() {
+        let mut f = File::open("input/day-2.txt").unwrap();
+        let sub: Direct = navigate(&mut f, false).unwrap();
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 741,
+                distance: 1998
+            }
+        );
+        assert_eq!(sub.pos.prod(), Ok(1480518));
+    }
+
+    #[test]
+    fn test_aim_example() {
+        let mut f = File::open("input/day-2-sample.txt").unwrap();
+        let sub: Aim = navigate(&mut f, false).unwrap();
+        assert_eq!(sub.aim, 10);
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 60,
+                distance: 15
+            }
+        );
+        assert_eq!(sub.pos.prod(), Ok(900));
+    }
+
+    #[test]
+    fn test_aim_full() {
+        let mut f = File::open("input/day-2.txt").unwrap();
+        let sub: Aim = navigate(&mut f, false).unwrap();
+        assert_eq!(sub.aim, 741);
+        assert_eq!(
+            sub.pos,
+            Position {
+                depth: 642047,
+                distance: 1998
+            }
+        );
+        assert_eq!(sub.pos.prod(), Ok(1282809906));
+    }
+}
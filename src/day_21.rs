@@ -0,0 +1,313 @@
+use crate::opts;
+use std::collections::HashMap;
+use std::io::Read;
+
+const ROLLS_PER_TURN: u32 = 3;
+
+#[derive(Clone, Copy, Debug)]
+struct Rules {
+    board: u32,
+    target: u32,
+    faces: u32,
+}
+
+impl Rules {
+    fn from_args(args: &[String], defaults: Rules) -> Rules {
+        Rules {
+            board: opts::value_or(args, "--board", defaults.board),
+            target: opts::value_or(args, "--target", defaults.target),
+            faces: opts::value_or(args, "--faces", defaults.faces),
+        }
+    }
+
+    fn check(&self, start: [u32; 2]) -> Result<(), String> {
+        if self.board == 0 {
+            return Err("--board must be at least 1".to_string());
+        }
+        if self.faces == 0 {
+            return Err("--faces must be at least 1".to_string());
+        }
+        match start.iter().find(|p| !(1..=self.board).contains(*p)) {
+            Some(p) => Err(format!(
+                "starting position {} is not on a board of {}",
+                p, self.board
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // Position after moving `steps` forward from `pos`, on a board
+    // numbered from 1 to `board`.
+    fn advance(&self, pos: u32, steps: u32) -> u32 {
+        (pos - 1 + steps) % self.board + 1
+    }
+}
+
+const DETERMINISTIC: Rules = Rules {
+    board: 10,
+    target: 1000,
+    faces: 100,
+};
+
+const DIRAC: Rules = Rules {
+    board: 10,
+    target: 21,
+    faces: 3,
+};
+
+fn load(input: &mut dyn Read) -> [u32; 2] {
+    use std::io::{BufRead, BufReader};
+
+    let positions: Vec<u32> = BufReader::new(input)
+        .lines()
+        .map(|l| l.unwrap())
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (_, pos) = l.split_once("starting position: ").unwrap();
+            pos.trim().parse().unwrap()
+        })
+        .collect();
+
+    assert_eq!(positions.len(), 2);
+    [positions[0], positions[1]]
+}
+
+fn play_deterministic(rules: &Rules, start: [u32; 2]) -> u64 {
+    let mut pos = start;
+    let mut score = [0u64; 2];
+    let mut rolls = 0u64;
+    let mut player = 0;
+
+    loop {
+        let steps: u32 = (0..ROLLS_PER_TURN)
+            .map(|_| {
+                rolls += 1;
+                ((rolls - 1) % u64::from(rules.faces)) as u32 + 1
+            })
+            .sum();
+
+        pos[player] = rules.advance(pos[player], steps);
+        score[player] += u64::from(pos[player]);
+
+        if score[player] >= u64::from(rules.target) {
+            return score[1 - player] * rolls;
+        }
+        player = 1 - player;
+    }
+}
+
+// How many different roll sequences produce each total in a single turn
+fn roll_sums(faces: u32) -> Vec<(u32, u128)> {
+    let mut counts: HashMap<u32, u128> = HashMap::new();
+    counts.insert(0, 1);
+
+    for _ in 0..ROLLS_PER_TURN {
+        let mut next = HashMap::new();
+        for (sum, cnt) in counts {
+            for face in 1..=faces {
+                *next.entry(sum + face).or_insert(0) += cnt;
+            }
+        }
+        counts = next;
+    }
+
+    let mut res: Vec<(u32, u128)> = counts.into_iter().collect();
+    res.sort_unstable();
+    res
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct State {
+    pos: [u32; 2],
+    score: [u32; 2],
+    turn: usize,
+}
+
+struct Multiverse {
+    rules: Rules,
+    sums: Vec<(u32, u128)>,
+    memo: HashMap<State, [u128; 2]>,
+}
+
+impl Multiverse {
+    fn new(rules: Rules) -> Multiverse {
+        Multiverse {
+            rules,
+            sums: roll_sums(rules.faces),
+            memo: HashMap::new(),
+        }
+    }
+
+    // Number of universes in which each player wins, starting from `state`,
+    // or None when the count does not fit in a u128
+    fn wins(&mut self, state: State) -> Option<[u128; 2]> {
+        if let Some(w) = self.memo.get(&state) {
+            return Some(*w);
+        }
+
+        let mut res = [0u128; 2];
+        let player = state.turn;
+
+        for i in 0..self.sums.len() {
+            let (steps, cnt) = self.sums[i];
+            let mut next = state;
+            next.pos[player] = self.rules.advance(state.pos[player], steps);
+            next.score[player] += next.pos[player];
+
+            if next.score[player] >= self.rules.target {
+                res[player] = res[player].checked_add(cnt)?;
+            } else {
+                next.turn = 1 - player;
+                let sub = self.wins(next)?;
+                for (r, s) in res.iter_mut().zip(sub) {
+                    *r = r.checked_add(cnt.checked_mul(s)?)?;
+                }
+            }
+        }
+
+        self.memo.insert(state, res);
+        Some(res)
+    }
+}
+
+fn play_dirac(rules: &Rules, start: [u32; 2]) -> Option<[u128; 2]> {
+    Multiverse::new(*rules).wins(State {
+        pos: start,
+        score: [0, 0],
+        turn: 0,
+    })
+}
+
+fn part2(rules: &Rules, start: [u32; 2]) -> Option<u128> {
+    let wins = play_dirac(rules, start)?;
+    Some(u128::max(wins[0], wins[1]))
+}
+
+// The rules from the command line and the starting positions from the
+// input, once they are known to make sense together
+fn load_checked(input: &mut dyn Read, args: &[String], defaults: Rules) -> (Rules, [u32; 2]) {
+    let rules = Rules::from_args(args, defaults);
+    let start = load(input);
+    if let Err(e) = rules.check(start) {
+        opts::fail(&e);
+    }
+    (rules, start)
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    let (rules, start) = load_checked(input, args, DETERMINISTIC);
+    println!("{}", play_deterministic(&rules, start));
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    let (rules, start) = load_checked(input, args, DIRAC);
+    match part2(&rules, start) {
+        Some(wins) => println!("{}", wins),
+        None => opts::fail("too many universes to count with these rules"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_load() {
+        let mut f = File::open("input/day-21-sample.txt").unwrap();
+        assert_eq!(load(&mut f), [4, 8]);
+    }
+
+    #[test]
+    fn test_roll_sums() {
+        assert_eq!(
+            roll_sums(3),
+            vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+    }
+
+    #[test]
+    fn test_part1_sample() {
+        let mut f = File::open("input/day-21-sample.txt").unwrap();
+        assert_eq!(play_deterministic(&DETERMINISTIC, load(&mut f)), 739785);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(DIRAC.check([4, 8]), Ok(()));
+        assert_eq!(DIRAC.check([10, 1]), Ok(()));
+        assert!(DIRAC.check([0, 8]).is_err());
+        assert!(DIRAC.check([4, 11]).is_err());
+        assert!(Rules { board: 0, ..DIRAC }.check([4, 8]).is_err());
+        assert!(Rules { faces: 0, ..DIRAC }.check([4, 8]).is_err());
+    }
+
+    #[test]
+    fn test_part2_sample() {
+        let wins = play_dirac(&DIRAC, [4, 8]);
+        assert_eq!(wins, Some([444356092776315, 341960390180808]));
+    }
+
+    #[test]
+    fn test_part2_variant() {
+        // With a target of one point, the first player always wins at once
+        let rules = Rules { target: 1, ..DIRAC };
+        assert_eq!(play_dirac(&rules, [4, 8]), Some([27, 0]));
+    }
+
+    // Plays out every single roll without merging equal sums or states
+    fn brute_force(rules: &Rules, pos: [u32; 2], score: [u32; 2], player: usize) -> [u128; 2] {
+        let mut res = [0u128; 2];
+        let mut totals = vec![0u32];
+        for _ in 0..ROLLS_PER_TURN {
+            totals = totals
+                .iter()
+                .flat_map(|t| (1..=rules.faces).map(move |f| t + f))
+                .collect();
+        }
+        for steps in totals {
+            let mut pos = pos;
+            let mut score = score;
+            pos[player] = rules.advance(pos[player], steps);
+            score[player] += pos[player];
+            if score[player] >= rules.target {
+                res[player] += 1;
+            } else {
+                let sub = brute_force(rules, pos, score, 1 - player);
+                res[0] += sub[0];
+                res[1] += sub[1];
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn test_part2_faces() {
+        let rules = Rules {
+            target: 4,
+            faces: 4,
+            ..DIRAC
+        };
+        assert_eq!(
+            play_dirac(&rules, [4, 8]),
+            Some(brute_force(&rules, [4, 8], [0, 0], 0))
+        );
+
+        // A four-sided die overflows a u64 on the full target
+        let rules = Rules { faces: 4, ..DIRAC };
+        let wins = play_dirac(&rules, [4, 8]).unwrap();
+        assert!(wins[0] > u128::from(u64::MAX));
+
+        let rules = Rules { faces: 10, ..DIRAC };
+        assert_eq!(play_dirac(&rules, [4, 8]), None);
+    }
+
+    #[test]
+    fn test_part1_large_target() {
+        let rules = Rules {
+            target: 100000000,
+            ..DETERMINISTIC
+        };
+        assert!(play_deterministic(&rules, [4, 8]) > u64::from(u32::MAX));
+    }
+}
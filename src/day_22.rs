@@ -0,0 +1,203 @@
+use std::io::Read;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Cuboid {
+    min: [i64; 3],
+    max: [i64; 3],
+}
+
+impl Cuboid {
+    fn from_text(txt: &str) -> Cuboid {
+        let mut min = [0; 3];
+        let mut max = [0; 3];
+
+        for (axis, range) in txt.split(',').enumerate() {
+            let (_, range) = range.split_once('=').unwrap();
+            let (lo, hi) = range.split_once("..").unwrap();
+            let (lo, hi): (i64, i64) = (lo.parse().unwrap(), hi.parse().unwrap());
+            min[axis] = i64::min(lo, hi);
+            max[axis] = i64::max(lo, hi);
+        }
+
+        Cuboid { min, max }
+    }
+
+    fn volume(&self) -> i64 {
+        (0..3).map(|a| self.max[a] - self.min[a] + 1).product()
+    }
+
+    fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let mut res = *self;
+        for a in 0..3 {
+            res.min[a] = i64::max(self.min[a], other.min[a]);
+            res.max[a] = i64::min(self.max[a], other.max[a]);
+            if res.min[a] > res.max[a] {
+                return None;
+            }
+        }
+        Some(res)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Step {
+    on: bool,
+    cuboid: Cuboid,
+}
+
+impl Step {
+    fn from_line(line: &str) -> Step {
+        let (state, ranges) = line.split_once(' ').unwrap();
+        let on = match state {
+            "on" => true,
+            "off" => false,
+            _ => panic!("bad step {:?}", line),
+        };
+
+        Step {
+            on,
+            cuboid: Cuboid::from_text(ranges),
+        }
+    }
+}
+
+const INIT_REGION: Cuboid = Cuboid {
+    min: [-50, -50, -50],
+    max: [50, 50, 50],
+};
+
+fn load(input: &mut dyn Read) -> Vec<Step> {
+    use std::io::{BufRead, BufReader};
+
+    BufReader::new(input)
+        .lines()
+        .map(|l| l.unwrap())
+        .filter(|l| !l.is_empty())
+        .map(|l| Step::from_line(&l))
+        .collect()
+}
+
+// Keeps a list of signed cuboids whose volumes sum up to the number of
+// lit cubes. Every step first cancels out whatever overlaps it so far,
+// and then adds itself if it turns things on.
+fn lit_count(steps: &[Step], region: Option<&Cuboid>) -> i64 {
+    let mut signed: Vec<(Cuboid, i64)> = Vec::new();
+
+    for step in steps {
+        let cuboid = match region {
+            Some(r) => match step.cuboid.intersection(r) {
+                Some(c) => c,
+                None => continue,
+            },
+            None => step.cuboid,
+        };
+
+        let overlaps: Vec<(Cuboid, i64)> = signed
+            .iter()
+            .filter_map(|(c, sign)| Some((c.intersection(&cuboid)?, -sign)))
+            .collect();
+        signed.extend(overlaps);
+
+        if step.on {
+            signed.push((cuboid, 1));
+        }
+    }
+
+    signed.iter().map(|(c, sign)| c.volume() * sign).sum()
+}
+
+fn part1(input: &mut dyn Read) -> i64 {
+    lit_count(&load(input), Some(&INIT_REGION))
+}
+
+fn part2(input: &mut dyn Read) -> i64 {
+    lit_count(&load(input), None)
+}
+
+pub fn run_part1(input: &mut dyn Read) {
+    println!("{}", part1(input));
+}
+
+pub fn run_part2(input: &mut dyn Read) {
+    println!("{}", part2(input));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::fs::File;
+
+    fn brute_force(steps: &[Step]) -> i64 {
+        let mut lit = HashSet::new();
+        for step in steps {
+            let Cuboid { min, max } = step.cuboid;
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    for z in min[2]..=max[2] {
+                        if step.on {
+                            lit.insert((x, y, z));
+                        } else {
+                            lit.remove(&(x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+        lit.len() as i64
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(
+            Step::from_line("off x=-54112..-39298,y=-85059..-49293,z=-27449..7877"),
+            Step {
+                on: false,
+                cuboid: Cuboid {
+                    min: [-54112, -85059, -27449],
+                    max: [-39298, -49293, 7877]
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_part1_sample() {
+        let mut f = File::open("input/day-22-sample.txt").unwrap();
+        assert_eq!(part1(&mut f), 39);
+    }
+
+    #[test]
+    fn test_part2_sample() {
+        let mut f = File::open("input/day-22-sample.txt").unwrap();
+        assert_eq!(part2(&mut f), 39);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let steps: Vec<Step> = [
+            "on x=-3..4,y=0..5,z=-2..2",
+            "off x=0..2,y=-1..3,z=0..6",
+            "on x=1..6,y=2..2,z=-5..5",
+            "on x=-1..1,y=-1..1,z=-1..1",
+            "off x=-10..10,y=4..4,z=-10..10",
+            "on x=2..3,y=1..7,z=2..3",
+        ]
+        .iter()
+        .map(|l| Step::from_line(l))
+        .collect();
+
+        assert_eq!(lit_count(&steps, None), brute_force(&steps));
+    }
+
+    #[test]
+    fn test_init_region() {
+        let steps: Vec<Step> = ["on x=-60..-40,y=0..0,z=0..0", "on x=100..200,y=0..0,z=0..0"]
+            .iter()
+            .map(|l| Step::from_line(l))
+            .collect();
+
+        assert_eq!(lit_count(&steps, Some(&INIT_REGION)), 11);
+        assert_eq!(lit_count(&steps, None), 21 + 101);
+    }
+}
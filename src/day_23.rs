@@ -0,0 +1,326 @@
+use crate::opts;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::io::Read;
+
+const HALL_LEN: usize = 11;
+const ROOMS: usize = 4;
+const EMPTY: u8 = b'.';
+
+// Rows inserted between the first and second row of rooms in part 2
+const UNFOLDED: [&[u8; ROOMS]; 2] = [b"DCBA", b"DBAC"];
+
+// Hallway cells 0..HALL_LEN, followed by each room from top to bottom
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Burrow {
+    depth: usize,
+    cells: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Place {
+    Hall(usize),
+    Room(usize, usize),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Move {
+    kind: u8,
+    from: Place,
+    to: Place,
+    energy: u32,
+}
+
+fn energy_per_step(kind: u8) -> u32 {
+    10u32.pow((kind - b'A') as u32)
+}
+
+fn door(room: usize) -> usize {
+    2 + 2 * room
+}
+
+fn is_door(pos: usize) -> bool {
+    (0..ROOMS).any(|r| door(r) == pos)
+}
+
+// Hallway positions strictly after `from` up to and including `to`
+fn hall_span(from: usize, to: usize) -> std::ops::RangeInclusive<usize> {
+    if from < to {
+        (from + 1)..=to
+    } else {
+        to..=(from - 1)
+    }
+}
+
+impl Burrow {
+    fn from_lines(lines: &[String]) -> Burrow {
+        let hall: Vec<u8> = lines[1].bytes().skip(1).take(HALL_LEN).collect();
+        let rows: Vec<Vec<u8>> = lines[2..]
+            .iter()
+            .map(|l| l.bytes().filter(|c| c.is_ascii_uppercase()).collect())
+            .filter(|r: &Vec<u8>| !r.is_empty())
+            .collect();
+        assert!(rows.iter().all(|r| r.len() == ROOMS));
+
+        let depth = rows.len();
+        let mut cells = hall;
+        for room in 0..ROOMS {
+            cells.extend(rows.iter().map(|r| r[room]));
+        }
+
+        Burrow { depth, cells }
+    }
+
+    fn unfold(&self) -> Burrow {
+        let depth = self.depth + UNFOLDED.len();
+        let mut cells = self.cells[..HALL_LEN].to_vec();
+        for room in 0..ROOMS {
+            let old = self.room(room);
+            cells.push(old[0]);
+            cells.extend(UNFOLDED.iter().map(|r| r[room]));
+            cells.extend_from_slice(&old[1..]);
+        }
+
+        Burrow { depth, cells }
+    }
+
+    fn room(&self, room: usize) -> &[u8] {
+        let start = HALL_LEN + room * self.depth;
+        &self.cells[start..start + self.depth]
+    }
+
+    fn cell(&self, place: Place) -> usize {
+        match place {
+            Place::Hall(pos) => pos,
+            Place::Room(room, d) => HALL_LEN + room * self.depth + d,
+        }
+    }
+
+    fn target_kind(room: usize) -> u8 {
+        b'A' + room as u8
+    }
+
+    fn room_settled(&self, room: usize) -> bool {
+        let kind = Burrow::target_kind(room);
+        self.room(room).iter().all(|c| *c == EMPTY || *c == kind)
+    }
+
+    fn organized(&self) -> bool {
+        (0..ROOMS).all(|r| self.room(r).iter().all(|c| *c == Burrow::target_kind(r)))
+    }
+
+    fn hall_clear(&self, from: usize, to: usize) -> bool {
+        hall_span(from, to).all(|pos| self.cells[pos] == EMPTY)
+    }
+
+    fn apply(&self, mv: &Move) -> Burrow {
+        let mut next = self.clone();
+        next.cells[self.cell(mv.from)] = EMPTY;
+        next.cells[self.cell(mv.to)] = mv.kind;
+        next
+    }
+
+    fn moves(&self) -> Vec<Move> {
+        let mut res = Vec::new();
+
+        // Out of a room into the hallway
+        for room in 0..ROOMS {
+            if self.room_settled(room) {
+                continue;
+            }
+            let d = match self.room(room).iter().position(|c| *c != EMPTY) {
+                Some(d) => d,
+                None => continue,
+            };
+            let kind = self.room(room)[d];
+
+            for pos in (0..HALL_LEN).filter(|p| !is_door(*p)) {
+                if self.hall_clear(door(room), pos) {
+                    let steps = d + 1 + pos.abs_diff(door(room));
+                    res.push(Move {
+                        kind,
+                        from: Place::Room(room, d),
+                        to: Place::Hall(pos),
+                        energy: steps as u32 * energy_per_step(kind),
+                    });
+                }
+            }
+        }
+
+        // From the hallway into the final room
+        for pos in 0..HALL_LEN {
+            let kind = self.cells[pos];
+            if kind == EMPTY {
+                continue;
+            }
+            let room = (kind - b'A') as usize;
+            if !self.room_settled(room) || !self.hall_clear(pos, door(room)) {
+                continue;
+            }
+            let d = self.room(room).iter().rposition(|c| *c == EMPTY).unwrap();
+            let steps = pos.abs_diff(door(room)) + d + 1;
+            res.push(Move {
+                kind,
+                from: Place::Hall(pos),
+                to: Place::Room(room, d),
+                energy: steps as u32 * energy_per_step(kind),
+            });
+        }
+
+        res
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", "#".repeat(HALL_LEN + 2))?;
+        writeln!(f, "#{}#", String::from_utf8_lossy(&self.cells[..HALL_LEN]))?;
+        for d in 0..self.depth {
+            let (edge, pad) = if d == 0 { ("##", "") } else { ("", "  ") };
+            write!(f, "{}{}", pad, edge)?;
+            for room in 0..ROOMS {
+                write!(f, "#{}", self.room(room)[d] as char)?;
+            }
+            writeln!(f, "#{}", edge)?;
+        }
+        write!(f, "  {}", "#".repeat(HALL_LEN - 2))
+    }
+}
+
+impl fmt::Display for Place {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Place::Hall(pos) => write!(f, "hallway {}", pos),
+            Place::Room(room, d) => write!(
+                f,
+                "room {} depth {}",
+                Burrow::target_kind(*room) as char,
+                d + 1
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} ({} energy)",
+            self.kind as char, self.from, self.to, self.energy
+        )
+    }
+}
+
+// Dijkstra over burrow states. Returns the least total energy and the
+// moves that reach it.
+fn organize(start: Burrow) -> (u32, Vec<Move>) {
+    let mut best: HashMap<Burrow, u32> = HashMap::new();
+    let mut previous: HashMap<Burrow, (Burrow, Move)> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    best.insert(start.clone(), 0);
+    queue.push(Reverse((0, start.clone())));
+
+    while let Some(Reverse((energy, burrow))) = queue.pop() {
+        if burrow.organized() {
+            let mut route = Vec::new();
+            let mut here = burrow;
+            while here != start {
+                let (prev, mv) = previous.remove(&here).unwrap();
+                route.push(mv);
+                here = prev;
+            }
+            route.reverse();
+            return (energy, route);
+        }
+
+        if energy > best[&burrow] {
+            continue;
+        }
+
+        for mv in burrow.moves() {
+            let next = burrow.apply(&mv);
+            let next_energy = energy + mv.energy;
+            if best.get(&next).is_none_or(|e| next_energy < *e) {
+                best.insert(next.clone(), next_energy);
+                previous.insert(next.clone(), (burrow.clone(), mv));
+                queue.push(Reverse((next_energy, next)));
+            }
+        }
+    }
+
+    panic!("The amphipods cannot be organized")
+}
+
+fn load(input: &mut dyn Read) -> Burrow {
+    use std::io::{BufRead, BufReader};
+
+    let lines: Vec<String> = BufReader::new(input).lines().map(|l| l.unwrap()).collect();
+    Burrow::from_lines(&lines)
+}
+
+fn print_solution(start: Burrow, args: &[String]) {
+    let (energy, moves) = organize(start.clone());
+    if opts::flag(args, "--moves") {
+        let mut burrow = start;
+        println!("{}\n", burrow);
+        for mv in moves.iter() {
+            burrow = burrow.apply(mv);
+            println!("{}\n{}\n", mv, burrow);
+        }
+    }
+    println!("{}", energy);
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    print_solution(load(input), args);
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    print_solution(load(input).unfold(), args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_load() {
+        let mut f = File::open("input/day-23-sample.txt").unwrap();
+        let burrow = load(&mut f);
+        assert_eq!(burrow.depth, 2);
+        assert_eq!(burrow.room(0), b"BA");
+        assert_eq!(burrow.room(3), b"DA");
+        assert_eq!(burrow.unfold().room(2), b"BBAC");
+    }
+
+    #[test]
+    fn test_display() {
+        let text = std::fs::read_to_string("input/day-23-sample.txt").unwrap();
+        let mut f = File::open("input/day-23-sample.txt").unwrap();
+        assert_eq!(format!("{}\n", load(&mut f)), text);
+    }
+
+    #[test]
+    fn test_part1_sample() {
+        let mut f = File::open("input/day-23-sample.txt").unwrap();
+        let (energy, moves) = organize(load(&mut f));
+        assert_eq!(energy, 12521);
+        assert_eq!(moves.iter().map(|m| m.energy).sum::<u32>(), energy);
+    }
+
+    #[test]
+    fn test_part2_sample() {
+        let mut f = File::open("input/day-23-sample.txt").unwrap();
+        let (energy, moves) = organize(load(&mut f).unfold());
+        assert_eq!(energy, 44169);
+
+        let mut f = File::open("input/day-23-sample.txt").unwrap();
+        let end = moves
+            .iter()
+            .fold(load(&mut f).unfold(), |burrow, mv| burrow.apply(mv));
+        assert!(end.organized());
+    }
+}
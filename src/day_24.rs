@@ -0,0 +1,357 @@
+use std::io::Read;
+
+const REGS: [char; 4] = ['w', 'x', 'y', 'z'];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Operand {
+    Reg(usize),
+    Num(i64),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Instr {
+    Inp(usize),
+    Bin(Op, usize, Operand),
+}
+
+fn reg_from_text(txt: &str) -> usize {
+    REGS.iter()
+        .position(|r| txt.len() == 1 && txt.starts_with(*r))
+        .unwrap_or_else(|| panic!("bad register {:?}", txt))
+}
+
+impl Operand {
+    fn from_text(txt: &str) -> Operand {
+        match txt.parse() {
+            Ok(n) => Operand::Num(n),
+            Err(_) => Operand::Reg(reg_from_text(txt)),
+        }
+    }
+}
+
+impl Instr {
+    fn from_line(line: &str) -> Instr {
+        let pieces: Vec<&str> = line.split_whitespace().collect();
+        match pieces[..] {
+            ["inp", a] => Instr::Inp(reg_from_text(a)),
+            [op, a, b] => {
+                let op = match op {
+                    "add" => Op::Add,
+                    "mul" => Op::Mul,
+                    "div" => Op::Div,
+                    "mod" => Op::Mod,
+                    "eql" => Op::Eql,
+                    _ => panic!("bad instruction {:?}", line),
+                };
+                Instr::Bin(op, reg_from_text(a), Operand::from_text(b))
+            }
+            _ => panic!("bad instruction {:?}", line),
+        }
+    }
+}
+
+fn load(input: &mut dyn Read) -> Vec<Instr> {
+    use std::io::{BufRead, BufReader};
+
+    BufReader::new(input)
+        .lines()
+        .map(|l| l.unwrap())
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| Instr::from_line(&l))
+        .collect()
+}
+
+fn execute(program: &[Instr], inputs: &[i64]) -> Result<[i64; 4], String> {
+    let mut regs = [0i64; 4];
+    let mut inputs = inputs.iter();
+
+    for (n, instr) in program.iter().enumerate() {
+        match *instr {
+            Instr::Inp(a) => {
+                regs[a] = *inputs
+                    .next()
+                    .ok_or_else(|| format!("instruction {}: out of input", n + 1))?;
+            }
+            Instr::Bin(op, a, b) => {
+                let lhs = regs[a];
+                let rhs = match b {
+                    Operand::Reg(r) => regs[r],
+                    Operand::Num(v) => v,
+                };
+                let res = match op {
+                    Op::Add => lhs.checked_add(rhs),
+                    Op::Mul => lhs.checked_mul(rhs),
+                    Op::Div if rhs == 0 => {
+                        return Err(format!("instruction {}: division by zero", n + 1))
+                    }
+                    Op::Div => lhs.checked_div(rhs),
+                    Op::Mod if lhs < 0 || rhs <= 0 => {
+                        return Err(format!(
+                            "instruction {}: mod {} {} is undefined",
+                            n + 1,
+                            lhs,
+                            rhs
+                        ))
+                    }
+                    Op::Mod => Some(lhs % rhs),
+                    Op::Eql => Some((lhs == rhs) as i64),
+                };
+                regs[a] = res.ok_or_else(|| {
+                    format!("instruction {}: {:?} {} {} overflows", n + 1, op, lhs, rhs)
+                })?;
+            }
+        }
+    }
+
+    Ok(regs)
+}
+
+// The parameters that differ between the per-digit blocks of MONAD. Each
+// block either pushes `w + offset` onto a base 26 stack kept in z, or pops
+// the top and only avoids pushing again when `w == top + check`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Block {
+    pops: bool,
+    check: i64,
+    offset: i64,
+}
+
+fn find_num(block: &[Instr], op: Op, reg: char, after: Option<Instr>) -> i64 {
+    let reg = reg_from_text(&reg.to_string());
+    block
+        .iter()
+        .enumerate()
+        .find_map(|(n, instr)| match *instr {
+            Instr::Bin(o, r, Operand::Num(v)) if o == op && r == reg => {
+                if after.is_none() || n > 0 && after == Some(block[n - 1]) {
+                    Some(v)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("block does not look like MONAD: {:?}", block))
+}
+
+fn blocks(program: &[Instr]) -> Vec<Block> {
+    let starts: Vec<usize> = program
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| matches!(i, Instr::Inp(_)))
+        .map(|(n, _)| n)
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, start)| {
+            let end = starts.get(n + 1).cloned().unwrap_or(program.len());
+            let block = &program[*start..end];
+            let add_y_w = Instr::Bin(Op::Add, 2, Operand::Reg(0));
+            let pops = match find_num(block, Op::Div, 'z', None) {
+                1 => false,
+                26 => true,
+                d => panic!("unexpected div z {}", d),
+            };
+            Block {
+                pops,
+                check: find_num(block, Op::Add, 'x', None),
+                offset: find_num(block, Op::Add, 'y', Some(add_y_w)),
+            }
+        })
+        .collect()
+}
+
+// Pairs every pushing block with the block that pops its value, and picks
+// the digits of each pair with `choose`, given the required difference
+// between the popping and the pushing digit.
+fn model_number(program: &[Instr], choose: fn(i64) -> Option<(i64, i64)>) -> Vec<i64> {
+    let blocks = blocks(program);
+    let mut digits = vec![0; blocks.len()];
+    let mut stack: Vec<(usize, i64)> = Vec::new();
+
+    for (n, block) in blocks.iter().enumerate() {
+        if block.pops {
+            let (pushed, offset) = stack.pop().expect("unbalanced MONAD program");
+            let diff = offset + block.check;
+            let (push_digit, pop_digit) = choose(diff).unwrap_or_else(|| {
+                panic!(
+                    "digits {} and {} would have to differ by {}",
+                    pushed + 1,
+                    n + 1,
+                    diff
+                )
+            });
+            digits[pushed] = push_digit;
+            digits[n] = pop_digit;
+        } else {
+            stack.push((n, block.offset));
+        }
+    }
+    assert!(stack.is_empty(), "unbalanced MONAD program");
+
+    assert_eq!(
+        execute(program, &digits).unwrap()[3],
+        0,
+        "analysis went wrong"
+    );
+    digits
+}
+
+// Both digits have to be from 1 to 9, which no pair can be when they
+// are more than 8 apart
+fn largest(diff: i64) -> Option<(i64, i64)> {
+    let push_digit = 9 - i64::max(diff, 0);
+    Some((push_digit, push_digit + diff)).filter(|_| diff.abs() <= 8)
+}
+
+fn smallest(diff: i64) -> Option<(i64, i64)> {
+    let push_digit = 1 - i64::min(diff, 0);
+    Some((push_digit, push_digit + diff)).filter(|_| diff.abs() <= 8)
+}
+
+fn digits_to_text(digits: &[i64]) -> String {
+    digits.iter().map(|d| d.to_string()).collect()
+}
+
+fn part1(input: &mut dyn Read) -> String {
+    digits_to_text(&model_number(&load(input), largest))
+}
+
+fn part2(input: &mut dyn Read) -> String {
+    digits_to_text(&model_number(&load(input), smallest))
+}
+
+pub fn run_part1(input: &mut dyn Read) {
+    println!("{}", part1(input));
+}
+
+pub fn run_part2(input: &mut dyn Read) {
+    println!("{}", part2(input));
+}
+
+pub fn run_program(input: &mut dyn Read, args: &[String]) {
+    let inputs: Vec<i64> = args
+        .iter()
+        .map(|a| {
+            a.parse()
+                .unwrap_or_else(|_| panic!("bad input value {:?}", a))
+        })
+        .collect();
+
+    let regs = match execute(&load(input), &inputs) {
+        Ok(regs) => regs,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    for (name, value) in REGS.iter().zip(regs) {
+        println!("{} = {}", name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn program(txt: &str) -> Vec<Instr> {
+        txt.lines().map(Instr::from_line).collect()
+    }
+
+    #[test]
+    fn test_instr() {
+        assert_eq!(Instr::from_line("inp w"), Instr::Inp(0));
+        assert_eq!(
+            Instr::from_line("add z -12"),
+            Instr::Bin(Op::Add, 3, Operand::Num(-12))
+        );
+        assert_eq!(
+            Instr::from_line("eql x w"),
+            Instr::Bin(Op::Eql, 1, Operand::Reg(0))
+        );
+    }
+
+    #[test]
+    fn test_negate() {
+        let p = program("inp x\nmul x -1");
+        assert_eq!(execute(&p, &[7]).unwrap()[1], -7);
+    }
+
+    #[test]
+    fn test_errors() {
+        let square = program("inp x\nmul x x\nmul x x");
+        assert_eq!(execute(&square, &[1000]).unwrap()[1], 1_000_000_000_000);
+        let err = execute(&square, &[1 << 20]).unwrap_err();
+        assert!(err.starts_with("instruction 3:"), "{}", err);
+        let err = execute(&square, &[]).unwrap_err();
+        assert!(err.starts_with("instruction 1:"), "{}", err);
+
+        assert!(execute(&program("div x 0"), &[]).is_err());
+        assert!(execute(&program("add x -1\nmod x 3"), &[]).is_err());
+    }
+
+    #[test]
+    fn test_choose() {
+        assert_eq!(largest(3), Some((6, 9)));
+        assert_eq!(largest(-8), Some((9, 1)));
+        assert_eq!(smallest(-2), Some((3, 1)));
+        assert_eq!(smallest(8), Some((1, 9)));
+        assert_eq!(largest(9), None);
+        assert_eq!(smallest(-9), None);
+    }
+
+    #[test]
+    fn test_binary() {
+        let p = program(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\n\
+             div w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        );
+        assert_eq!(execute(&p, &[11]).unwrap(), [1, 0, 1, 1]);
+        assert_eq!(execute(&p, &[6]).unwrap(), [0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_full() {
+        let mut f = File::open("input/day-24.txt").unwrap();
+        let p = load(&mut f);
+        assert_eq!(blocks(&p).len(), 14);
+
+        let max = model_number(&p, largest);
+        let min = model_number(&p, smallest);
+        assert_eq!(digits_to_text(&max), "65984919997939");
+        assert_eq!(digits_to_text(&min), "11211619541713");
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        // The first four blocks of the input, with a balanced stack
+        let mut f = File::open("input/day-24.txt").unwrap();
+        let full = load(&mut f);
+        let p: Vec<Instr> = full[..18]
+            .iter()
+            .chain(full[36..72].iter())
+            .chain(full[90..108].iter())
+            .cloned()
+            .collect();
+
+        let accepted: Vec<Vec<i64>> = (0..9 * 9 * 9 * 9)
+            .map(|n: i64| (0..4).rev().map(|k| 1 + n / 9i64.pow(k) % 9).collect())
+            .filter(|digits: &Vec<i64>| execute(&p, digits).unwrap()[3] == 0)
+            .collect();
+
+        assert_eq!(model_number(&p, largest), *accepted.last().unwrap());
+        assert_eq!(model_number(&p, smallest), *accepted.first().unwrap());
+    }
+}
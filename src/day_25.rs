@@ -0,0 +1,164 @@
+use crate::opts;
+use std::io::Read;
+
+const EMPTY: u8 = b'.';
+const EAST: u8 = b'>';
+const SOUTH: u8 = b'v';
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Seafloor {
+    cells: Vec<u8>,
+    cols: usize,
+}
+
+impl Seafloor {
+    fn parse(lines: impl Iterator<Item = String>) -> Result<Seafloor, String> {
+        let mut cols = None;
+        let mut cells = Vec::new();
+
+        for (n, line) in lines.enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(c) = line.chars().find(|c| !".>v".contains(*c)) {
+                return Err(format!("line {}: unexpected character {:?}", n + 1, c));
+            }
+            let expected = *cols.get_or_insert(line.len());
+            if line.len() != expected {
+                return Err(format!(
+                    "line {}: {} cells where earlier lines have {}",
+                    n + 1,
+                    line.len(),
+                    expected
+                ));
+            }
+            cells.extend(line.bytes());
+        }
+
+        match cols {
+            Some(cols) => Ok(Seafloor { cells, cols }),
+            None => Err("no seafloor in the input".to_string()),
+        }
+    }
+
+    fn from_input(input: &mut dyn Read) -> Seafloor {
+        use std::io::{BufRead, BufReader};
+
+        Seafloor::parse(BufReader::new(input).lines().map(|l| l.unwrap()))
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn rows(&self) -> usize {
+        self.cells.len() / self.cols
+    }
+
+    // Moves every member of one herd at once. Returns how many moved.
+    fn move_herd(&mut self, herd: u8) -> usize {
+        let cols = self.cols;
+        let rows = self.rows();
+
+        let moving: Vec<(usize, usize)> = (0..self.cells.len())
+            .filter(|pos| self.cells[*pos] == herd)
+            .map(|pos| {
+                let (row, col) = (pos / cols, pos % cols);
+                let dest = if herd == EAST {
+                    row * cols + (col + 1) % cols
+                } else {
+                    (row + 1) % rows * cols + col
+                };
+                (pos, dest)
+            })
+            .filter(|(_, dest)| self.cells[*dest] == EMPTY)
+            .collect();
+
+        for (pos, dest) in moving.iter() {
+            self.cells[*pos] = EMPTY;
+            self.cells[*dest] = herd;
+        }
+
+        moving.len()
+    }
+
+    fn step(&mut self) -> usize {
+        self.move_herd(EAST) + self.move_herd(SOUTH)
+    }
+
+    fn to_text(&self) -> String {
+        self.cells
+            .chunks(self.cols)
+            .map(|row| String::from_utf8_lossy(row) + "\n")
+            .collect()
+    }
+}
+
+// Number of the first step on which nothing moves. Calls `frame` with the
+// step number and the seafloor after each step.
+fn first_still_step(mut floor: Seafloor, mut frame: impl FnMut(u32, &Seafloor)) -> u32 {
+    let mut steps = 0;
+    loop {
+        steps += 1;
+        let moved = floor.step();
+        frame(steps, &floor);
+        if moved == 0 {
+            return steps;
+        }
+    }
+}
+
+fn part1(input: &mut dyn Read) -> u32 {
+    first_still_step(Seafloor::from_input(input), |_, _| {})
+}
+
+pub fn run(input: &mut dyn Read, args: &[String]) {
+    if !opts::flag(args, "--frames") {
+        println!("{}", part1(input));
+        return;
+    }
+
+    let floor = Seafloor::from_input(input);
+    println!("Initial state:\n{}", floor.to_text());
+    let steps = first_still_step(floor, |step, f| {
+        println!("After {} steps:\n{}", step, f.to_text())
+    });
+    println!("{}", steps);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_step() {
+        let mut floor = Seafloor::from_input(&mut "...>>>>>...".as_bytes());
+        floor.step();
+        assert_eq!(floor.to_text(), "...>>>>.>..\n");
+        floor.step();
+        assert_eq!(floor.to_text(), "...>>>.>.>.\n");
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let mut floor = Seafloor::from_input(&mut "..v\n..>\n...".as_bytes());
+        floor.step();
+        assert_eq!(floor.to_text(), "...\n>.v\n...\n");
+        floor.step();
+        assert_eq!(floor.to_text(), "...\n.>.\n..v\n");
+    }
+
+    #[test]
+    fn test_parse() {
+        let parse = |txt: &str| Seafloor::parse(txt.lines().map(|l| l.to_string()));
+        assert_eq!(parse("..v\n>..\n").unwrap().rows(), 2);
+        assert!(parse("").is_err());
+        assert!(parse("\n\n").is_err());
+        assert!(parse("..v>\n..\n").is_err());
+        assert!(parse("..v\n.x.\n").is_err());
+    }
+
+    #[test]
+    fn test_part1_sample() {
+        let mut f = File::open("input/day-25-sample.txt").unwrap();
+        assert_eq!(part1(&mut f), 58);
+    }
+}
@@ -0,0 +1,505 @@
+use crate::opts;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::str::FromStr;
+
+// A binary number of any width, most significant bit first
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Bits(Vec<bool>);
+
+impl Bits {
+    fn from_text(txt: &str) -> Result<Bits, String> {
+        txt.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(format!("unexpected character {:?}", c)),
+            })
+            .collect::<Result<Vec<bool>, String>>()
+            .map(Bits)
+    }
+
+    fn value(&self) -> Option<u64> {
+        if self.0.len() > 64 {
+            return None;
+        }
+        Some(self.0.iter().fold(0, |acc, bit| acc << 1 | *bit as u64))
+    }
+}
+
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bit in self.0.iter() {
+            write!(f, "{}", if *bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+// The report stored column by column, with the bits of each column packed
+// into words so that a whole column can be counted with popcount.
+struct Report {
+    width: usize,
+    rows: usize,
+    columns: Vec<Vec<u64>>,
+}
+
+const WORD_BITS: usize = 64;
+
+// A set of rows, as a mask with the same layout as a column
+type RowSet = Vec<u64>;
+
+fn count(set: &[u64]) -> usize {
+    set.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+impl Report {
+    fn row(&self, row: usize) -> Bits {
+        let (word, bit) = (row / WORD_BITS, row % WORD_BITS);
+        Bits(
+            self.columns
+                .iter()
+                .map(|c| c[word] >> bit & 1 != 0)
+                .collect(),
+        )
+    }
+
+    fn all_rows(&self) -> RowSet {
+        let mut set = vec![u64::MAX; self.rows / WORD_BITS];
+        let tail = self.rows % WORD_BITS;
+        if tail > 0 {
+            set.push((1 << tail) - 1);
+        }
+        set
+    }
+
+    fn ones_within(&self, col: usize, set: &[u64]) -> usize {
+        self.columns[col]
+            .iter()
+            .zip(set)
+            .map(|(c, s)| (c & s).count_ones() as usize)
+            .sum()
+    }
+}
+
+fn parse_report(lines: impl Iterator<Item = String>) -> Result<Report, String> {
+    let mut width = None;
+    let mut rows = 0;
+    let mut columns: Vec<Vec<u64>> = Vec::new();
+
+    for (n, line) in lines.enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let bits = Bits::from_text(&line).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        let expected = *width.get_or_insert(bits.0.len());
+        if bits.0.len() != expected {
+            return Err(format!(
+                "line {}: {} bits where earlier lines have {}",
+                n + 1,
+                bits.0.len(),
+                expected
+            ));
+        }
+
+        if columns.is_empty() {
+            columns.resize(expected, Vec::new());
+        }
+        let (word, bit) = (rows / WORD_BITS, rows % WORD_BITS);
+        for (col, set) in columns.iter_mut().zip(bits.0) {
+            if bit == 0 {
+                col.push(0);
+            }
+            col[word] |= (set as u64) << bit;
+        }
+        rows += 1;
+    }
+
+    Ok(Report {
+        width: width.unwrap_or(0),
+        rows,
+        columns,
+    })
+}
+
+fn load(input: &mut dyn Read) -> Report {
+    parse_report(BufReader::new(input).lines().map(|l| l.unwrap()))
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+// Which bit counts as the most common one when a column has as many ones
+// as zeros. The least common bit is then the other one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Tie {
+    One,
+    Zero,
+}
+
+impl FromStr for Tie {
+    type Err = String;
+
+    fn from_str(txt: &str) -> Result<Tie, String> {
+        match txt {
+            "one" | "1" => Ok(Tie::One),
+            "zero" | "0" => Ok(Tie::Zero),
+            _ => Err(format!("unknown tie rule {:?}", txt)),
+        }
+    }
+}
+
+fn most_common(one_cnt: usize, zero_cnt: usize, tie: Tie) -> bool {
+    match one_cnt.cmp(&zero_cnt) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => tie == Tie::One,
+    }
+}
+
+fn find_gamma_epsilon(report: &Report, tie: Tie) -> (Bits, Bits) {
+    let gamma: Vec<bool> = report
+        .columns
+        .iter()
+        .map(|col| {
+            let one_cnt = count(col);
+            most_common(one_cnt, report.rows - one_cnt, tie)
+        })
+        .collect();
+    let epsilon = gamma.iter().map(|b| !b).collect();
+
+    (Bits(gamma), Bits(epsilon))
+}
+
+enum Param {
+    Ogr,
+    Csr,
+}
+
+// Narrows down the candidates one column at a time by masking the set of
+// remaining rows, so each column costs one pass over its packed words.
+fn find(param: Param, report: &Report, tie: Tie) -> Bits {
+    find_with(param, report, tie, &mut |_| {})
+}
+
+// What happened at one bit position while narrowing down the candidates
+struct Narrowing<'a> {
+    bit: usize,
+    one_cnt: usize,
+    zero_cnt: usize,
+    target: Option<bool>,
+    remaining: &'a [u64],
+}
+
+fn find_with(param: Param, report: &Report, tie: Tie, visit: &mut dyn FnMut(&Narrowing)) -> Bits {
+    let mut remaining = report.all_rows();
+    let mut remaining_cnt = report.rows;
+
+    for b in 0..report.width {
+        if remaining_cnt <= 1 {
+            break;
+        }
+
+        let one_cnt = report.ones_within(b, &remaining);
+        let zero_cnt = remaining_cnt - one_cnt;
+
+        // When every candidate has the same bit here, there is nothing to
+        // choose between, whichever bit would be the target.
+        if one_cnt == 0 || zero_cnt == 0 {
+            visit(&Narrowing {
+                bit: b,
+                one_cnt,
+                zero_cnt,
+                target: None,
+                remaining: &remaining,
+            });
+            continue;
+        }
+
+        let most_common_at_b = most_common(one_cnt, zero_cnt, tie);
+        let target = match param {
+            Param::Ogr => most_common_at_b,
+            Param::Csr => !most_common_at_b,
+        };
+
+        for (r, c) in remaining.iter_mut().zip(report.columns[b].iter()) {
+            *r &= if target { *c } else { !*c };
+        }
+        remaining_cnt = if target { one_cnt } else { zero_cnt };
+
+        visit(&Narrowing {
+            bit: b,
+            one_cnt,
+            zero_cnt,
+            target: Some(target),
+            remaining: &remaining,
+        });
+    }
+
+    let word = remaining.iter().position(|w| *w != 0).unwrap();
+    report.row(word * WORD_BITS + remaining[word].trailing_zeros() as usize)
+}
+
+fn find_ogr_csr(report: &Report, tie: Tie) -> (Bits, Bits) {
+    (find(Param::Ogr, report, tie), find(Param::Csr, report, tie))
+}
+
+fn explain(report: &Report, step: &Narrowing) -> String {
+    let choice = match step.target {
+        Some(target) => format!("keeping {}", target as u8),
+        None => "all candidates agree".to_string(),
+    };
+    let mut text = format!(
+        "bit {}: {} ones, {} zeros, {}\n",
+        step.bit + 1,
+        step.one_cnt,
+        step.zero_cnt,
+        choice
+    );
+
+    for (w, word) in step.remaining.iter().enumerate() {
+        for bit in (0..WORD_BITS).filter(|b| word >> b & 1 != 0) {
+            text += &format!("    {}\n", report.row(w * WORD_BITS + bit));
+        }
+    }
+    text
+}
+
+fn find_explained(param: Param, report: &Report, tie: Tie) -> (Bits, String) {
+    let mut text = String::new();
+    let rating = find_with(param, report, tie, &mut |step| {
+        text += &explain(report, step)
+    });
+    (rating, text)
+}
+
+fn print_product(a: &Bits, b: &Bits) {
+    match (a.value(), b.value()) {
+        (Some(a), Some(b)) => println!("{} * {} -> {}", a, b, a as u128 * b as u128),
+        _ => println!("{} * {}", a, b),
+    }
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    let tie = opts::value_or(args, "--tie", Tie::One);
+    let (gamma, epsilon) = find_gamma_epsilon(&load(input), tie);
+    print_product(&gamma, &epsilon);
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    let tie = opts::value_or(args, "--tie", Tie::One);
+    let report = load(input);
+
+    let (ogr, csr) = if opts::flag(args, "--explain") {
+        let (ogr, ogr_text) = find_explained(Param::Ogr, &report, tie);
+        let (csr, csr_text) = find_explained(Param::Csr, &report, tie);
+        println!("oxygen generator rating:\n{}", ogr_text);
+        println!("CO2 scrubber rating:\n{}", csr_text);
+        (ogr, csr)
+    } else {
+        find_ogr_csr(&report, tie)
+    };
+    print_product(&ogr, &csr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use std::fs::File;
+
+    fn report(txt: &str) -> Result<Report, String> {
+        parse_report(txt.lines().map(|l| l.to_string()))
+    }
+
+    #[test]
+    fn test_bits() {
+        let bits = Bits::from_text("10110").unwrap();
+        assert_eq!(bits.value(), Some(22));
+        assert_eq!(bits.to_string(), "10110");
+        assert!(Bits::from_text("10120").is_err());
+
+        let wide = Bits::from_text(&"1".repeat(65)).unwrap();
+        assert_eq!(wide.value(), None);
+    }
+
+    #[test]
+    fn test_inconsistent_width() {
+        let err = report("0101\n111\n").err().unwrap();
+        assert!(err.starts_with("line 2:"));
+    }
+
+    #[test]
+    fn test_wide_report() {
+        // The sample with 67 zero bits appended to every line
+        let text = std::fs::read_to_string("input/day-3-sample.txt").unwrap();
+        let padding = "0".repeat(67);
+        let r = report(&text.replace('\n', &format!("{}\n", padding))).unwrap();
+        assert_eq!(r.width, 72);
+
+        let (gamma, epsilon) = find_gamma_epsilon(&r, Tie::One);
+        assert_eq!(gamma.to_string(), format!("10110{}", padding));
+        assert_eq!(epsilon.to_string(), format!("01001{}", "1".repeat(67)));
+
+        let (ogr, csr) = find_ogr_csr(&r, Tie::One);
+        assert_eq!(ogr.to_string(), format!("10111{}", padding));
+        assert_eq!(csr.to_string(), format!("01010{}", padding));
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        // Every line starts with zeros, so only the line length tells the width
+        let text: String = std::fs::read_to_string("input/day-3-sample.txt")
+            .unwrap()
+            .lines()
+            .map(|l| format!("000{}\n", l))
+            .collect();
+        let r = report(&text).unwrap();
+        assert_eq!(r.width, 8);
+
+        let (gamma, epsilon) = find_gamma_epsilon(&r, Tie::One);
+        assert_eq!(gamma.value(), Some(22));
+        assert_eq!(epsilon.value(), Some(0b11101001));
+
+        let (ogr, csr) = find_ogr_csr(&r, Tie::One);
+        assert_eq!(ogr.value(), Some(23));
+        assert_eq!(csr.value(), Some(10));
+    }
+
+    #[test]
+    fn test_gamma_ties() {
+        let r = report("10\n01\n").unwrap();
+
+        let (gamma, epsilon) = find_gamma_epsilon(&r, Tie::One);
+        assert_eq!((gamma.value(), epsilon.value()), (Some(0b11), Some(0b00)));
+
+        let (gamma, epsilon) = find_gamma_epsilon(&r, Tie::Zero);
+        assert_eq!((gamma.value(), epsilon.value()), (Some(0b00), Some(0b11)));
+    }
+
+    #[test]
+    fn test_rating_ties() {
+        let r = report("10\n01\n11\n00\n").unwrap();
+
+        let (ogr, csr) = find_ogr_csr(&r, Tie::One);
+        assert_eq!((ogr.value(), csr.value()), (Some(0b11), Some(0b00)));
+
+        let (ogr, csr) = find_ogr_csr(&r, Tie::Zero);
+        assert_eq!((ogr.value(), csr.value()), (Some(0b00), Some(0b11)));
+    }
+
+    #[test]
+    fn test_tie_from_str() {
+        assert_eq!("one".parse(), Ok(Tie::One));
+        assert_eq!("0".parse(), Ok(Tie::Zero));
+        assert!("maybe".parse::<Tie>().is_err());
+    }
+
+    // Straightforward row by row versions of both parts to check against
+    fn naive(rows: &[Vec<bool>], tie: Tie) -> (Vec<bool>, Vec<bool>, Vec<bool>) {
+        let width = rows[0].len();
+        let gamma = (0..width)
+            .map(|b| {
+                let ones = rows.iter().filter(|r| r[b]).count();
+                most_common(ones, rows.len() - ones, tie)
+            })
+            .collect();
+
+        let rating = |keep_most: bool| {
+            let mut remaining: Vec<&Vec<bool>> = rows.iter().collect();
+            for b in 0..width {
+                let ones = remaining.iter().filter(|r| r[b]).count();
+                let zeros = remaining.len() - ones;
+                if remaining.len() > 1 && ones > 0 && zeros > 0 {
+                    let target = most_common(ones, zeros, tie) == keep_most;
+                    remaining.retain(|r| r[b] == target);
+                }
+            }
+            remaining[0].clone()
+        };
+
+        (gamma, rating(true), rating(false))
+    }
+
+    #[test]
+    fn test_against_naive() {
+        let mut rng = Rng::with_seed(12345);
+        let mut rows = Vec::new();
+        for _ in 0..1000 {
+            let row: Vec<bool> = (0..20).map(|_| rng.below(2) == 1).collect();
+            rows.push(row);
+        }
+        let text: String = rows
+            .iter()
+            .map(|r| format!("{}\n", Bits(r.clone())))
+            .collect();
+        let r = report(&text).unwrap();
+        assert_eq!(r.rows, 1000);
+
+        for tie in [Tie::One, Tie::Zero] {
+            let (gamma, ogr, csr) = naive(&rows, tie);
+            assert_eq!(find_gamma_epsilon(&r, tie).0, Bits(gamma));
+            assert_eq!(find_ogr_csr(&r, tie), (Bits(ogr), Bits(csr)));
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut f = File::open("input/day-3-sample.txt").unwrap();
+        let r = load(&mut f);
+
+        let (csr, text) = find_explained(Param::Csr, &r, Tie::One);
+        assert_eq!(csr.value(), Some(10));
+        assert_eq!(
+            text,
+            "bit 1: 7 ones, 5 zeros, keeping 0\n    \
+             00100\n    01111\n    00111\n    00010\n    01010\n\
+             bit 2: 2 ones, 3 zeros, keeping 1\n    \
+             01111\n    01010\n\
+             bit 3: 1 ones, 1 zeros, keeping 0\n    \
+             01010\n"
+        );
+
+        let (ogr, text) = find_explained(Param::Ogr, &r, Tie::One);
+        assert_eq!(ogr.value(), Some(23));
+        assert_eq!(text.lines().filter(|l| l.starts_with("bit")).count(), 5);
+        assert!(text.ends_with("bit 5: 1 ones, 1 zeros, keeping 1\n    10111\n"));
+    }
+
+    #[test]
+    fn test_part1_example() {
+        let mut f = File::open("input/day-3-sample.txt").unwrap();
+        let (gamma, epsilon) = find_gamma_epsilon(&load(&mut f), Tie::One);
+
+        assert_eq!(gamma.value(), Some(22));
+        assert_eq!(epsilon.value(), Some(9));
+    }
+
+    #[test]
+    fn test_part1_full() {
+        let mut f = File::open("input/day-3.txt").unwrap();
+        let (gamma, epsilon) = find_gamma_epsilon(&load(&mut f), Tie::One);
+
+        assert_eq!(gamma.value(), Some(784));
+        assert_eq!(epsilon.value(), Some(3311));
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let mut f = File::open("input/day-3-sample.txt").unwrap();
+        let (ogr, csr) = find_ogr_csr(&load(&mut f), Tie::One);
+
+        assert_eq!(ogr.value(), Some(23));
+        assert_eq!(csr.value(), Some(10));
+    }
+
+    #[test]
+    fn test_part2_full() {
+        let mut f = File::open("input/day-3.txt").unwrap();
+        let (ogr, csr) = find_ogr_csr(&load(&mut f), Tie::One);
+
+        assert_eq!(ogr.value(), Some(781));
+        assert_eq!(csr.value(), Some(2734));
+    }
+}
@@ -0,0 +1,904 @@
+use crate::opts;
+use crate::rng::Rng;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+
+struct Board {
+    size: usize,
+    nums: Vec<u32>,
+}
+
+// One bit per cell of the board, in as many words as it takes
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Marks(Vec<u64>);
+
+impl Marks {
+    fn set(&mut self, pos: usize) {
+        if self.0.len() <= pos / 64 {
+            self.0.resize(pos / 64 + 1, 0);
+        }
+        self.0[pos / 64] |= 1 << (pos % 64);
+    }
+
+    fn get(&self, pos: usize) -> bool {
+        self.0
+            .get(pos / 64)
+            .is_some_and(|w| w >> (pos % 64) & 1 != 0)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(n, w)| {
+            (0..64)
+                .filter(move |bit| w >> bit & 1 != 0)
+                .map(move |bit| n * 64 + bit)
+        })
+    }
+
+    fn covers(&self, other: &Marks) -> bool {
+        other
+            .0
+            .iter()
+            .enumerate()
+            .all(|(n, w)| self.0.get(n).unwrap_or(&0) & w == *w)
+    }
+
+    fn from_cells(cells: impl Iterator<Item = usize>) -> Marks {
+        let mut marks = Marks::default();
+        for pos in cells {
+            marks.set(pos);
+        }
+        marks
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Rule {
+    Rows,
+    Cols,
+    Diagonals,
+    Corners,
+    Blackout,
+}
+
+// A pattern from a rule file, as the cells of a board of the given size
+#[derive(Debug, Eq, PartialEq)]
+struct Custom {
+    size: usize,
+    cells: Vec<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Rules {
+    standard: Vec<Rule>,
+    custom: Vec<Custom>,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            standard: vec![Rule::Rows, Rule::Cols],
+            custom: Vec::new(),
+        }
+    }
+}
+
+// Rule files list the winning patterns one per line: rows, columns,
+// diagonals, corners or blackout. A line with just "pattern" starts a
+// custom pattern, given as the following rows of X (part of the pattern)
+// and . (not part of it).
+fn parse_rules(lines: impl Iterator<Item = String>) -> Result<Rules, String> {
+    let mut rules = Rules {
+        standard: Vec::new(),
+        custom: Vec::new(),
+    };
+    let mut pattern: Option<Vec<String>> = None;
+
+    for (n, raw) in lines.chain(std::iter::once(String::new())).enumerate() {
+        let line = raw.split('#').next().unwrap().trim();
+
+        if let Some(rows) = pattern.as_mut() {
+            if !line.is_empty() && line.chars().all(|c| c == 'X' || c == '.') {
+                rows.push(line.to_string());
+                continue;
+            }
+            let rows = pattern.take().unwrap();
+            let size = rows.len();
+            if size == 0 || rows.iter().any(|r| r.len() != size) {
+                return Err(format!("line {}: patterns must be square", n + 1));
+            }
+            let cells: Vec<usize> = rows
+                .concat()
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| *c == 'X')
+                .map(|(pos, _)| pos)
+                .collect();
+            if cells.is_empty() {
+                return Err(format!("line {}: pattern has no X in it", n + 1));
+            }
+            rules.custom.push(Custom { size, cells });
+        }
+
+        let rule = match line {
+            "" => continue,
+            "pattern" => {
+                pattern = Some(Vec::new());
+                continue;
+            }
+            "rows" => Rule::Rows,
+            "columns" => Rule::Cols,
+            "diagonals" => Rule::Diagonals,
+            "corners" => Rule::Corners,
+            "blackout" => Rule::Blackout,
+            _ => return Err(format!("line {}: unknown rule {:?}", n + 1, line)),
+        };
+        rules.standard.push(rule);
+    }
+
+    Ok(rules)
+}
+
+fn load_rules(path: &str) -> Rules {
+    let f = std::fs::File::open(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    parse_rules(BufReader::new(f).lines().map(|l| l.unwrap()))
+        .unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Pattern {
+    Row(usize),
+    Col(usize),
+    Diagonal,
+    AntiDiagonal,
+    Corners,
+    Blackout,
+    Custom(usize),
+}
+
+struct WinMask {
+    pattern: Pattern,
+    mask: Marks,
+}
+
+// Every way to win on a board of the given size, in the order they are
+// checked. Checking a board is then a matter of comparing its marks with
+// each mask.
+fn win_masks(rules: &Rules, size: usize) -> Result<Vec<WinMask>, String> {
+    let mut masks = Vec::new();
+    let mut add = |pattern, cells: &mut dyn Iterator<Item = usize>| {
+        masks.push(WinMask {
+            pattern,
+            mask: Marks::from_cells(cells),
+        })
+    };
+
+    for rule in rules.standard.iter() {
+        match rule {
+            Rule::Rows => (0..size)
+                .for_each(|row| add(Pattern::Row(row), &mut (0..size).map(|c| row * size + c))),
+            Rule::Cols => (0..size)
+                .for_each(|col| add(Pattern::Col(col), &mut (0..size).map(|r| r * size + col))),
+            Rule::Diagonals => {
+                add(Pattern::Diagonal, &mut (0..size).map(|i| i * size + i));
+                add(
+                    Pattern::AntiDiagonal,
+                    &mut (0..size).map(|i| i * size + size - 1 - i),
+                );
+            }
+            Rule::Corners => add(
+                Pattern::Corners,
+                &mut [0, size - 1, size * (size - 1), size * size - 1].into_iter(),
+            ),
+            Rule::Blackout => add(Pattern::Blackout, &mut (0..size * size)),
+        }
+    }
+
+    for (n, custom) in rules.custom.iter().enumerate() {
+        if custom.size != size {
+            return Err(format!(
+                "pattern {} is {} by {}, but the boards are {} by {}",
+                n + 1,
+                custom.size,
+                custom.size,
+                size,
+                size
+            ));
+        }
+        add(Pattern::Custom(n), &mut custom.cells.iter().cloned());
+    }
+
+    Ok(masks)
+}
+
+fn winning_pattern(masks: &[WinMask], marks: &Marks) -> Option<Pattern> {
+    masks
+        .iter()
+        .find(|m| marks.covers(&m.mask))
+        .map(|m| m.pattern)
+}
+
+fn parse_nums_and_boards(
+    mut lines: impl Iterator<Item = String>,
+) -> Result<(Vec<u32>, Vec<Board>), String> {
+    let nums: Vec<u32> = lines
+        .next()
+        .ok_or("no numbers to draw")?
+        .split(',')
+        .map(|s| s.trim().parse().map_err(|_| format!("bad number {:?}", s)))
+        .collect::<Result<Vec<u32>, String>>()?;
+
+    let mut boards = Vec::new();
+    let mut board: Vec<u32> = Vec::new();
+    let mut size = 0;
+
+    // Line numbers start from the first board line
+    for (n, line) in lines.chain(std::iter::once(String::new())).enumerate() {
+        let row: Vec<u32> = line
+            .split_whitespace()
+            .map(|s| s.parse().map_err(|_| format!("bad number {:?}", s)))
+            .collect::<Result<Vec<u32>, String>>()?;
+
+        if row.is_empty() {
+            if !board.is_empty() {
+                if board.len() != size * size {
+                    return Err(format!(
+                        "board ending on line {} has {} rows, expected {}",
+                        n + 2,
+                        board.len() / size,
+                        size
+                    ));
+                }
+                // Each number marks a single cell
+                let mut sorted = board.clone();
+                sorted.sort_unstable();
+                if let Some(pair) = sorted.windows(2).find(|p| p[0] == p[1]) {
+                    return Err(format!(
+                        "board ending on line {} has {} twice",
+                        n + 2,
+                        pair[0]
+                    ));
+                }
+                boards.push(Board {
+                    size,
+                    nums: std::mem::take(&mut board),
+                });
+            }
+            continue;
+        }
+
+        if size == 0 {
+            size = row.len();
+        }
+        if row.len() != size || board.len() == size * size {
+            return Err(format!(
+                "line {}: boards must be {} by {}",
+                n + 2,
+                size,
+                size
+            ));
+        }
+        board.extend(row);
+    }
+
+    Ok((nums, boards))
+}
+
+fn nums_and_boards(input: &mut dyn Read) -> (Vec<u32>, Vec<Board>) {
+    parse_nums_and_boards(BufReader::new(input).lines().map(|l| l.unwrap()))
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Win {
+    draw: usize,
+    last_num: u32,
+    pattern: Pattern,
+    sum: u64,
+}
+
+impl Win {
+    // Even a u64 is not enough for the largest sums times the largest numbers
+    fn score(&self) -> u128 {
+        u128::from(self.sum) * u128::from(self.last_num)
+    }
+}
+
+// Where one board ended up: boards that never win have no `win`
+#[derive(Debug, Eq, PartialEq)]
+struct Rank {
+    board: usize,
+    win: Option<Win>,
+}
+
+struct Bingo<'a> {
+    board: &'a Board,
+    marks: Marks,
+}
+
+impl<'a> Bingo<'a> {
+    fn with_board(board: &'a Board) -> Bingo<'a> {
+        Bingo {
+            board,
+            marks: Marks::default(),
+        }
+    }
+
+    fn draw(&mut self, num: u32, masks: &[WinMask]) -> Option<Pattern> {
+        let pos = self.board.nums.iter().position(|n| *n == num)?;
+
+        self.marks.set(pos);
+        winning_pattern(masks, &self.marks)
+    }
+
+    fn sum_of_unmarked(&self) -> u64 {
+        self.board
+            .nums
+            .iter()
+            .enumerate()
+            .filter(|(pos, _)| !self.marks.get(*pos))
+            .map(|(_, num)| u64::from(*num))
+            .sum()
+    }
+}
+
+// Plays the whole game, marking every board after every draw
+fn simulate(nums: &[u32], boards: &[Board], masks: &[WinMask]) -> Vec<Option<Win>> {
+    let mut bingos: Vec<Bingo> = boards.iter().map(Bingo::with_board).collect();
+    let mut wins: Vec<Option<Win>> = vec![None; bingos.len()];
+    let mut playing = bingos.len();
+
+    for (draw, num) in nums.iter().enumerate() {
+        if playing == 0 {
+            break;
+        }
+
+        for (bingo, win) in bingos.iter_mut().zip(wins.iter_mut()) {
+            if win.is_some() {
+                continue;
+            }
+            if let Some(pattern) = bingo.draw(*num, masks) {
+                *win = Some(Win {
+                    draw,
+                    last_num: *num,
+                    pattern,
+                    sum: bingo.sum_of_unmarked(),
+                });
+                playing -= 1;
+            }
+        }
+    }
+
+    wins
+}
+
+// Finds the wins without playing: a pattern is complete on the last draw
+// of any of its numbers, and a board wins on the earliest of those.
+fn solve(nums: &[u32], boards: &[Board], masks: &[WinMask]) -> Vec<Option<Win>> {
+    let mut draw_of: HashMap<u32, usize> = HashMap::new();
+    for (draw, num) in nums.iter().enumerate().rev() {
+        draw_of.insert(*num, draw);
+    }
+    let lines: Vec<(Pattern, Vec<usize>)> = masks
+        .iter()
+        .map(|m| (m.pattern, m.mask.cells().collect()))
+        .collect();
+
+    boards
+        .iter()
+        .map(|board| {
+            let draws: Vec<usize> = board
+                .nums
+                .iter()
+                .map(|n| draw_of.get(n).cloned().unwrap_or(usize::MAX))
+                .collect();
+            let (pattern, draw) = lines
+                .iter()
+                .map(|(pattern, cells)| {
+                    (*pattern, cells.iter().map(|c| draws[*c]).max().unwrap_or(0))
+                })
+                .min_by_key(|(_, draw)| *draw)?;
+            if draw == usize::MAX {
+                return None;
+            }
+
+            Some(Win {
+                draw,
+                last_num: nums[draw],
+                pattern,
+                sum: board
+                    .nums
+                    .iter()
+                    .zip(draws.iter())
+                    .filter(|(_, d)| **d > draw)
+                    .map(|(n, _)| u64::from(*n))
+                    .sum(),
+            })
+        })
+        .collect()
+}
+
+type Solver = fn(&[u32], &[Board], &[WinMask]) -> Vec<Option<Win>>;
+
+// The masks are shared by all boards, so they all have to be the same size
+fn board_masks(boards: &[Board], rules: &Rules) -> Result<Vec<WinMask>, String> {
+    let size = match boards.first() {
+        Some(b) => b.size,
+        None => return Ok(Vec::new()),
+    };
+    if let Some(n) = boards.iter().position(|b| b.size != size) {
+        return Err(format!(
+            "board {} is {} by {}, but board 1 is {} by {}",
+            n + 1,
+            boards[n].size,
+            boards[n].size,
+            size,
+            size
+        ));
+    }
+    win_masks(rules, size)
+}
+
+// Orders the boards by the draw on which they win, boards that never win
+// last.
+fn ranking(nums: &[u32], boards: &[Board], rules: &Rules, solver: Solver) -> Vec<Rank> {
+    let masks = board_masks(boards, rules).unwrap_or_else(|e| panic!("{}", e));
+    let mut ranks: Vec<Rank> = solver(nums, boards, &masks)
+        .into_iter()
+        .enumerate()
+        .map(|(board, win)| Rank { board, win })
+        .collect();
+    ranks.sort_by_key(|r| (r.win.map_or(usize::MAX, |w| w.draw), r.board));
+    ranks
+}
+
+fn ranking_of_input(input: &mut dyn Read, rules: &Rules, solver: Solver) -> Vec<Rank> {
+    let (nums, boards) = nums_and_boards(input);
+    ranking(&nums, &boards, rules, solver)
+}
+
+fn rules_from_args(args: &[String]) -> Rules {
+    match opts::value::<String>(args, "--rules") {
+        Some(path) => load_rules(&path),
+        None => Rules::default(),
+    }
+}
+
+// Ranking with the rules and solver picked by `--rules FILE` and
+// `--simulate`
+fn ranking_with_args(input: &mut dyn Read, args: &[String]) -> Vec<Rank> {
+    let rules = rules_from_args(args);
+    let solver: Solver = if opts::flag(args, "--simulate") {
+        simulate
+    } else {
+        solve
+    };
+    ranking_of_input(input, &rules, solver)
+}
+
+// How likely a board is to be the first and the last to win
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Odds {
+    first: f64,
+    last: f64,
+}
+
+// Plays the game with the draws shuffled `trials` times. Boards that win
+// on the same draw share the credit for it.
+fn odds(nums: &[u32], boards: &[Board], rules: &Rules, trials: u32, seed: u64) -> Vec<Odds> {
+    let masks = board_masks(boards, rules).unwrap_or_else(|e| panic!("{}", e));
+    let mut rng = Rng::with_seed(seed);
+    let mut nums = nums.to_vec();
+    let mut odds = vec![Odds::default(); boards.len()];
+
+    for _ in 0..trials {
+        rng.shuffle(&mut nums);
+        let draws: Vec<Option<usize>> = solve(&nums, boards, &masks)
+            .iter()
+            .map(|w| w.map(|w| w.draw))
+            .collect();
+        let (first, last) = match (draws.iter().flatten().min(), draws.iter().flatten().max()) {
+            (Some(f), Some(l)) => (Some(*f), Some(*l)),
+            _ => continue,
+        };
+        let firsts = draws.iter().filter(|d| **d == first).count() as f64;
+        let lasts = draws.iter().filter(|d| **d == last).count() as f64;
+
+        for (o, d) in odds.iter_mut().zip(draws.iter()) {
+            if *d == first {
+                o.first += 1.0 / firsts;
+            }
+            if *d == last {
+                o.last += 1.0 / lasts;
+            }
+        }
+    }
+
+    if trials > 0 {
+        for o in odds.iter_mut() {
+            o.first /= trials as f64;
+            o.last /= trials as f64;
+        }
+    }
+    odds
+}
+
+fn first_win(ranks: &[Rank]) -> Win {
+    ranks.first().and_then(|r| r.win).expect("No victories!")
+}
+
+// Of the boards that finish together on the last winning draw, the one
+// with the lowest score
+fn last_win(ranks: &[Rank]) -> Win {
+    let wins = || ranks.iter().filter_map(|r| r.win);
+    let last = wins().map(|w| w.draw).max().expect("No victories!");
+    wins()
+        .filter(|w| w.draw == last)
+        .min_by_key(|w| w.score())
+        .unwrap()
+}
+
+fn print_win(win: Win) {
+    println!("{} * {} -> {}", win.sum, win.last_num, win.score());
+}
+
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    print_win(first_win(&ranking_with_args(input, args)));
+}
+
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    print_win(last_win(&ranking_with_args(input, args)));
+}
+
+pub fn run_ranking(input: &mut dyn Read, args: &[String]) {
+    let ranks = ranking_with_args(input, args);
+    for (n, rank) in ranks.iter().enumerate() {
+        match rank.win {
+            Some(w) => println!(
+                "{}. board {} wins on draw {} ({}) with {:?}, score {}",
+                n + 1,
+                rank.board + 1,
+                w.draw + 1,
+                w.last_num,
+                w.pattern,
+                w.score()
+            ),
+            None => println!("{}. board {} never wins", n + 1, rank.board + 1),
+        }
+    }
+}
+
+pub fn run_odds(input: &mut dyn Read, args: &[String]) {
+    let (nums, boards) = nums_and_boards(input);
+    let trials = opts::value_or(args, "--trials", 10000);
+    let seed = opts::value_or(args, "--seed", 2021);
+    let odds = odds(&nums, &boards, &rules_from_args(args), trials, seed);
+
+    for (n, o) in odds.iter().enumerate() {
+        println!(
+            "board {}: first {:.2}%, last {:.2}%",
+            n + 1,
+            o.first * 100.0,
+            o.last * 100.0
+        );
+    }
+    if let Some((n, _)) = odds
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.last.total_cmp(&b.last))
+    {
+        println!("let the squid win with board {}", n + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    #[test]
+    fn test_victorycond() {
+        let masks = win_masks(&Rules::default(), 5).unwrap();
+        let marks = |m: u64| Marks(vec![m]);
+        assert_eq!(
+            winning_pattern(&masks, &marks(0b0000_00000_00000_11111_00000)),
+            Some(Pattern::Row(1))
+        );
+        assert_eq!(
+            winning_pattern(&masks, &marks(0b0000_00000_00000_10111_00000)),
+            None
+        );
+        assert_eq!(
+            winning_pattern(&masks, &marks(0b01000_01000_01000_01000_01000)),
+            Some(Pattern::Col(3))
+        );
+        assert_eq!(
+            winning_pattern(&masks, &marks(0b00100_01000_01000_01000_01000)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_large_board() {
+        // A 9x9 board needs more than 64 marks, the last column wins
+        let masks = win_masks(&Rules::default(), 9).unwrap();
+        let mut marks = Marks::default();
+        for row in 0..9 {
+            assert_eq!(winning_pattern(&masks, &marks), None);
+            marks.set(9 * row + 8);
+        }
+        assert_eq!(winning_pattern(&masks, &marks), Some(Pattern::Col(8)));
+        assert_eq!(marks.0.len(), 2);
+    }
+
+    const RULES: &str = "# Anything but rows and columns
+diagonals
+corners
+blackout
+
+pattern
+X...X
+.X.X.
+..X..
+.X.X.
+X...X
+";
+
+    fn rules(txt: &str) -> Result<Rules, String> {
+        parse_rules(txt.lines().map(|l| l.to_string()))
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let r = rules(RULES).unwrap();
+        assert_eq!(
+            r.standard,
+            vec![Rule::Diagonals, Rule::Corners, Rule::Blackout]
+        );
+        assert_eq!(
+            r.custom,
+            vec![Custom {
+                size: 5,
+                cells: vec![0, 4, 6, 8, 12, 16, 18, 20, 24]
+            }]
+        );
+
+        assert!(rules("rows\nsideways\n").is_err());
+        assert!(rules("pattern\nX.\nX\n").is_err());
+        assert!(rules("pattern\n..\n..\n").is_err());
+        assert!(rules("pattern\n..\n..").is_err());
+        assert!(win_masks(&rules("pattern\nX.\n.X\n").unwrap(), 5).is_err());
+    }
+
+    #[test]
+    fn test_rule_patterns() {
+        let masks = win_masks(&rules(RULES).unwrap(), 5).unwrap();
+        let marks = |cells: &[usize]| Marks::from_cells(cells.iter().cloned());
+
+        assert_eq!(winning_pattern(&masks, &marks(&[0, 1, 2, 3, 4])), None);
+        assert_eq!(
+            winning_pattern(&masks, &marks(&[0, 6, 12, 18, 24])),
+            Some(Pattern::Diagonal)
+        );
+        assert_eq!(
+            winning_pattern(&masks, &marks(&[4, 8, 12, 16, 20, 3])),
+            Some(Pattern::AntiDiagonal)
+        );
+        assert_eq!(
+            winning_pattern(&masks, &marks(&[0, 4, 20, 24])),
+            Some(Pattern::Corners)
+        );
+        assert_eq!(
+            winning_pattern(&masks, &Marks::from_cells(0..25)),
+            Some(Pattern::Diagonal)
+        );
+
+        let blackout = win_masks(&rules("blackout").unwrap(), 5).unwrap();
+        assert_eq!(winning_pattern(&blackout, &Marks::from_cells(0..24)), None);
+        assert_eq!(
+            winning_pattern(&blackout, &Marks::from_cells(0..25)),
+            Some(Pattern::Blackout)
+        );
+
+        let custom =
+            win_masks(&rules(&RULES[RULES.find("pattern").unwrap()..]).unwrap(), 5).unwrap();
+        assert_eq!(
+            winning_pattern(&custom, &marks(&[0, 4, 6, 8, 12, 16, 18, 20, 24])),
+            Some(Pattern::Custom(0))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let lines = |txt: &str| txt.lines().map(|l| l.to_string()).collect::<Vec<String>>();
+
+        let (nums, boards) =
+            parse_nums_and_boards(lines("300,2\n\n300 1000\n 4 5\n\n\n1 2\n3 4\n").into_iter())
+                .unwrap();
+        assert_eq!(nums, vec![300, 2]);
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].size, 2);
+        assert_eq!(boards[0].nums, vec![300, 1000, 4, 5]);
+
+        let short = lines("1,2\n\n1 2 3\n4 5 6\n\n1 2 3\n4 5 6\n7 8 9\n");
+        assert!(parse_nums_and_boards(short.into_iter()).is_err());
+
+        let ragged = lines("1,2\n\n1 2\n4 5 6\n");
+        assert!(parse_nums_and_boards(ragged.into_iter()).is_err());
+
+        let repeated = lines("1,2\n\n1 2\n3 1\n");
+        assert!(parse_nums_and_boards(repeated.into_iter()).is_err());
+    }
+
+    #[test]
+    fn test_ranking() {
+        let mut f = File::open("input/day-4-sample.txt").unwrap();
+        let (nums, mut boards) = nums_and_boards(&mut f);
+        boards.push(Board {
+            size: 5,
+            nums: (100..125).collect(),
+        });
+
+        let ranks = ranking(&nums, &boards, &Rules::default(), solve);
+        let order: Vec<usize> = ranks.iter().map(|r| r.board).collect();
+        assert_eq!(order, vec![2, 0, 1, 3]);
+
+        let first = ranks[0].win.unwrap();
+        assert_eq!(first.draw, 11);
+        assert_eq!(first.pattern, Pattern::Row(0));
+        assert_eq!(first.score(), 4512);
+        assert_eq!(ranks[3].win, None);
+    }
+
+    #[test]
+    fn test_mixed_sizes() {
+        let boards = vec![
+            Board {
+                size: 2,
+                nums: vec![1, 2, 3, 4],
+            },
+            Board {
+                size: 1,
+                nums: vec![5],
+            },
+        ];
+        assert!(board_masks(&boards, &Rules::default()).is_err());
+        assert_eq!(
+            board_masks(&boards[..1], &Rules::default()).unwrap().len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_last_win_tie() {
+        // Boards 0 and 1 both finish on the last draw, board 0 with the
+        // lower score
+        let boards = vec![
+            Board {
+                size: 2,
+                nums: vec![1, 5, 3, 7],
+            },
+            Board {
+                size: 2,
+                nums: vec![1, 50, 3, 60],
+            },
+            Board {
+                size: 2,
+                nums: vec![1, 2, 8, 9],
+            },
+        ];
+        for solver in [simulate as Solver, solve] {
+            let ranks = ranking(&[1, 2, 3], &boards, &Rules::default(), solver);
+            assert_eq!(first_win(&ranks).score(), 17 * 2);
+            let win = last_win(&ranks);
+            assert_eq!((win.draw, win.sum, win.score()), (2, 12, 36));
+        }
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let boards = vec![Board {
+            size: 2,
+            nums: vec![4000000000, 4000000001, 4000000002, 4000000003],
+        }];
+        let nums = [4000000000, 4000000001];
+        for solver in [simulate as Solver, solve] {
+            let ranks = ranking(&nums, &boards, &Rules::default(), solver);
+            let win = first_win(&ranks);
+            assert_eq!(win.sum, 8000000005);
+            assert_eq!(win.score(), 32000000028000000005);
+        }
+    }
+
+    #[test]
+    fn test_solvers_agree() {
+        let rule_sets = [
+            Rules::default(),
+            rules("diagonals\ncorners").unwrap(),
+            rules("blackout").unwrap(),
+            rules(RULES).unwrap(),
+        ];
+        for path in ["input/day-4-sample.txt", "input/day-4.txt"] {
+            for r in rule_sets.iter() {
+                let mut f = File::open(path).unwrap();
+                let simulated = ranking_of_input(&mut f, r, simulate);
+                let mut f = File::open(path).unwrap();
+                assert_eq!(ranking_of_input(&mut f, r, solve), simulated);
+            }
+        }
+    }
+
+    #[test]
+    fn test_odds() {
+        let mut f = File::open("input/day-4-sample.txt").unwrap();
+        let (nums, mut boards) = nums_and_boards(&mut f);
+        let sample = odds(&nums, &boards, &Rules::default(), 1000, 7);
+        let first: f64 = sample.iter().map(|o| o.first).sum();
+        let last: f64 = sample.iter().map(|o| o.last).sum();
+        assert!((first - 1.0).abs() < 1e-9);
+        assert!((last - 1.0).abs() < 1e-9);
+        assert_eq!(sample, odds(&nums, &boards, &Rules::default(), 1000, 7));
+
+        // Identical boards always win together
+        boards.truncate(1);
+        let copy = Board {
+            size: 5,
+            nums: boards[0].nums.clone(),
+        };
+        boards.push(copy);
+        let twins = odds(&nums, &boards, &Rules::default(), 100, 7);
+        assert_eq!(
+            twins,
+            vec![
+                Odds {
+                    first: 0.5,
+                    last: 0.5
+                };
+                2
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blackout_ranking() {
+        let mut f = File::open("input/day-4-sample.txt").unwrap();
+        let ranks = ranking_of_input(&mut f, &rules("blackout").unwrap(), solve);
+        // Every number up to 26 gets drawn, so every board is cleared
+        assert!(ranks
+            .iter()
+            .all(|r| r.win.is_some_and(|w| w.pattern == Pattern::Blackout)));
+    }
+
+    #[test]
+    fn test_part1_example() {
+        let mut f = File::open("input/day-4-sample.txt").unwrap();
+        let win = first_win(&ranking_of_input(&mut f, &Rules::default(), solve));
+        assert_eq!(win.sum, 188);
+        assert_eq!(win.last_num, 24);
+    }
+
+    #[test]
+    fn test_part1_full() {
+        let mut f = File::open("input/day-4.txt").unwrap();
+        let win = first_win(&ranking_of_input(&mut f, &Rules::default(), solve));
+        assert_eq!(win.sum, 870);
+        assert_eq!(win.last_num, 41);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let mut f = File::open("input/day-4-sample.txt").unwrap();
+        let win = last_win(&ranking_of_input(&mut f, &Rules::default(), solve));
+        assert_eq!(win.sum, 148);
+        assert_eq!(win.last_num, 13);
+    }
+
+    #[test]
+    fn test_part2_full() {
+        let mut f = File::open("input/day-4.txt").unwrap();
+        let win = last_win(&ranking_of_input(&mut f, &Rules::default(), solve));
+        assert_eq!(win.sum, 258);
+        assert_eq!(win.last_num, 88);
+    }
+}
@@ -1,10 +1,14 @@
+use crate::opts;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
+use std::io::Write;
 
 struct Point {
-    x: u16,
-    y: u16,
+    x: u32,
+    y: u32,
 }
 
 impl Point {
@@ -27,106 +31,450 @@ impl Line {
             Point::from_text(point_parts.next().unwrap()),
         )
     }
-}
 
-fn ascending<T>(a: T, b: T) -> (T, T)
-where
-    T: Ord,
-{
-    if a <= b {
-        (a, b)
-    } else {
-        (b, a)
+    fn class(&self) -> Class {
+        let Line(p0, p1) = self;
+        let (x0, y0, x1, y1) = (p0.x as i64, p0.y as i64, p1.x as i64, p1.y as i64);
+        if x0 == x1 {
+            Class::Vertical
+        } else if y0 == y1 {
+            Class::Horizontal
+        } else if x0 - y0 == x1 - y1 {
+            Class::Ascending
+        } else if x0 + y0 == x1 + y1 {
+            Class::Descending
+        } else {
+            Class::Other
+        }
     }
-}
 
-fn part1(input: &mut dyn Read) -> u32 {
-    let pitch = 1024;
-    let buf_sz = 1048576;
-    let mut buf: Vec<u16> = Vec::new();
-    buf.resize(buf_sz, 0);
+    // Every point from the start of the line to its end. This is Bresenham's
+    // algorithm, which steps straight along the line for all but `Other`.
+    fn points(&self) -> impl Iterator<Item = (u32, u32)> {
+        let Line(p0, p1) = self;
+        let (mut x, mut y) = (p0.x as i64, p0.y as i64);
+        let (x1, y1) = (p1.x as i64, p1.y as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut err = dx + dy;
+        let mut done = false;
 
-    for Line(p0, p1) in BufReader::new(input)
-        .lines()
-        .map(|l| Line::from_text(&l.unwrap()))
-    {
-        if p0.x == p1.x {
-            /* Vertical line */
-            let x = p0.x;
-            let (y_min, y_max) = ascending(p0.y, p1.y);
-            for y in y_min..=y_max {
-                buf[x as usize + pitch * (y as usize)] += 1;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
             }
-        } else if p0.y == p1.y {
-            /* Horizontal line */
-            let y = p0.y;
-            let (x_min, x_max) = ascending(p0.x, p1.x);
-            for x in x_min..=x_max {
-                buf[x as usize + pitch * (y as usize)] += 1;
+            let point = (x as u32, y as u32);
+            if x == x1 && y == y1 {
+                done = true;
+            } else {
+                let e2 = 2 * err;
+                if e2 >= dy {
+                    err += dy;
+                    x += sx;
+                }
+                if e2 <= dx {
+                    err += dx;
+                    y += sy;
+                }
             }
-        }
+            Some(point)
+        })
     }
+}
 
-    buf.into_iter().filter(|n| *n > 1).count() as u32
+// Ascending diagonals go down and right, with y growing along with x
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Class {
+    Horizontal,
+    Vertical,
+    Ascending,
+    Descending,
+    Other,
 }
 
-fn part2(input: &mut dyn Read) -> u32 {
-    let pitch = 1024;
-    let buf_sz = 1048576;
-    let mut buf: Vec<u16> = Vec::new();
-    buf.resize(buf_sz, 0);
+const STRAIGHT: &[Class] = &[Class::Horizontal, Class::Vertical];
+const WITH_DIAGONALS: &[Class] = &[
+    Class::Horizontal,
+    Class::Vertical,
+    Class::Ascending,
+    Class::Descending,
+];
 
-    for Line(p0, p1) in BufReader::new(input)
+fn load(input: &mut dyn Read) -> Vec<Line> {
+    BufReader::new(input)
         .lines()
         .map(|l| Line::from_text(&l.unwrap()))
-    {
-        if p0.x == p1.x {
-            /* Vertical line */
-            let x = p0.x;
-            let (y_min, y_max) = ascending(p0.y, p1.y);
-            for y in y_min..=y_max {
-                buf[x as usize + pitch * (y as usize)] += 1;
-            }
-        } else if p0.y == p1.y {
-            /* Horizontal line */
-            let y = p0.y;
-            let (x_min, x_max) = ascending(p0.x, p1.x);
-            for x in x_min..=x_max {
-                buf[x as usize + pitch * (y as usize)] += 1;
-            }
-        } else if p0.x + p1.y == p0.y + p1.x {
-            /* Ascending diagonal */
-            let (x_min, x_max) = ascending(p0.x, p1.x);
-            let (y_min, y_max) = ascending(p0.y, p1.y);
-            for (x, y) in (x_min..=x_max).zip(y_min..=y_max) {
-                buf[x as usize + pitch * (y as usize)] += 1;
+        .collect()
+}
+
+// Grids with more cells than this are kept in a hash map instead
+const DENSE_LIMIT: u64 = 1 << 24;
+
+// How many lines cover each point. Uses a grid just big enough for the
+// lines when that is small enough, a map of the covered points otherwise.
+enum VentMap {
+    Dense {
+        x0: u32,
+        y0: u32,
+        width: usize,
+        cells: Vec<u16>,
+    },
+    Sparse(HashMap<(u32, u32), u16>),
+}
+
+impl VentMap {
+    fn for_lines(lines: &[Line]) -> VentMap {
+        let points = || lines.iter().flat_map(|Line(p0, p1)| [p0, p1]);
+        let (x0, x1) = match (points().map(|p| p.x).min(), points().map(|p| p.x).max()) {
+            (Some(x0), Some(x1)) => (x0, x1),
+            _ => return VentMap::Sparse(HashMap::new()),
+        };
+        let y0 = points().map(|p| p.y).min().unwrap();
+        let y1 = points().map(|p| p.y).max().unwrap();
+
+        let width = (x1 - x0) as u64 + 1;
+        let height = (y1 - y0) as u64 + 1;
+        if width.saturating_mul(height) > DENSE_LIMIT {
+            return VentMap::Sparse(HashMap::new());
+        }
+
+        VentMap::Dense {
+            x0,
+            y0,
+            width: width as usize,
+            cells: vec![0; (width * height) as usize],
+        }
+    }
+
+    fn add(&mut self, x: u32, y: u32) {
+        let count = match self {
+            VentMap::Dense {
+                x0,
+                y0,
+                width,
+                cells,
+            } => &mut cells[(x - *x0) as usize + *width * (y - *y0) as usize],
+            VentMap::Sparse(counts) => counts.entry((x, y)).or_insert(0),
+        };
+        *count = count.saturating_add(1);
+    }
+
+    // The counts row by row, if they are kept in a grid
+    fn grid(&self) -> Option<Grid<'_>> {
+        match self {
+            VentMap::Dense {
+                x0,
+                y0,
+                width,
+                cells,
+            } => Some(Grid {
+                x0: *x0,
+                y0: *y0,
+                width: *width,
+                cells,
+            }),
+            VentMap::Sparse(_) => None,
+        }
+    }
+
+    fn overlaps(&self) -> u32 {
+        match self {
+            VentMap::Dense { cells, .. } => cells.iter().filter(|n| **n > 1).count() as u32,
+            VentMap::Sparse(counts) => counts.values().filter(|n| **n > 1).count() as u32,
+        }
+    }
+}
+
+// Draws the lines of the given classes, leaving out the rest
+fn draw_lines(lines: &[Line], classes: &[Class]) -> VentMap {
+    let mut map = VentMap::for_lines(lines);
+    for line in lines.iter().filter(|l| classes.contains(&l.class())) {
+        for (x, y) in line.points() {
+            map.add(x, y);
+        }
+    }
+    map
+}
+
+fn count_overlaps(lines: &[Line], classes: &[Class]) -> u32 {
+    draw_lines(lines, classes).overlaps()
+}
+
+// Numbers of the lines that are not horizontal, vertical or diagonal
+fn other_lines(lines: &[Line]) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.class() == Class::Other)
+        .map(|(n, _)| n + 1)
+        .collect()
+}
+
+// Only spans the lines, so the top left cell is at x0,y0
+struct Grid<'a> {
+    x0: u32,
+    y0: u32,
+    width: usize,
+    cells: &'a [u16],
+}
+
+impl Grid<'_> {
+    fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    fn max(&self) -> u16 {
+        self.cells.iter().cloned().max().unwrap_or(0)
+    }
+
+    // Like the diagrams in the puzzle text, with # for ten or more lines.
+    // Those start at 0,0, so other corners are noted above the diagram.
+    fn to_text(&self) -> String {
+        let mut text = if (self.x0, self.y0) == (0, 0) {
+            String::new()
+        } else {
+            format!("top left at {},{}\n", self.x0, self.y0)
+        };
+        for row in self.cells.chunks(self.width) {
+            text.extend(row.iter().map(|n| match n {
+                0 => '.',
+                1..=9 => (b'0' + *n as u8) as char,
+                _ => '#',
+            }));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+// Maps larger than this in either direction are not printed as text
+const TEXT_LIMIT: usize = 100;
+
+fn scaled(n: u16, max: u16) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        n as f64 / max as f64
+    }
+}
+
+// Greyscale, brighter where more lines overlap
+fn write_pgm(out: &mut dyn Write, grid: &Grid) -> std::io::Result<()> {
+    let max = grid.max();
+    writeln!(out, "P5\n{} {}\n255", grid.width, grid.height())?;
+    let pixels: Vec<u8> = grid
+        .cells
+        .iter()
+        .map(|n| (scaled(*n, max) * 255.0).round() as u8)
+        .collect();
+    out.write_all(&pixels)
+}
+
+// Heat map going from black through red and yellow to white
+fn write_ppm(out: &mut dyn Write, grid: &Grid) -> std::io::Result<()> {
+    let max = grid.max();
+    writeln!(out, "P6\n{} {}\n255", grid.width, grid.height())?;
+    let channel = |t: f64| (t.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let pixels: Vec<u8> = grid
+        .cells
+        .iter()
+        .flat_map(|n| {
+            let t = scaled(*n, max) * 3.0;
+            [channel(t), channel(t - 1.0), channel(t - 2.0)]
+        })
+        .collect();
+    out.write_all(&pixels)
+}
+
+fn export(path: &str, grid: &Grid, write: fn(&mut dyn Write, &Grid) -> std::io::Result<()>) {
+    let res = std::fs::File::create(path).and_then(|mut f| write(&mut f, grid));
+    if let Err(e) = res {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(1);
+    }
+}
+
+// A horizontal, vertical or 45 degree line as its start, a unit step and
+// the number of steps to the end. Lines are turned around as needed so
+// that parallel lines step the same way.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    x: i64,
+    y: i64,
+    dx: i64,
+    dy: i64,
+    len: i64,
+}
+
+impl Segment {
+    fn from_line(line: &Line) -> Option<Segment> {
+        if line.class() == Class::Other {
+            return None;
+        }
+        let Line(p0, p1) = line;
+        let (x0, y0, x1, y1) = (p0.x as i64, p0.y as i64, p1.x as i64, p1.y as i64);
+        let (w, h) = (x1 - x0, y1 - y0);
+
+        let seg = Segment {
+            x: x0,
+            y: y0,
+            dx: w.signum(),
+            dy: h.signum(),
+            len: w.abs().max(h.abs()),
+        };
+        Some(match (seg.dx, seg.dy) {
+            (0, 0) => Segment { dx: 1, ..seg },
+            (-1, _) | (0, -1) => Segment {
+                x: x1,
+                y: y1,
+                dx: -seg.dx,
+                dy: -seg.dy,
+                ..seg
+            },
+            _ => seg,
+        })
+    }
+
+    fn at(&self, step: i64) -> (i64, i64) {
+        (self.x + step * self.dx, self.y + step * self.dy)
+    }
+
+    // Adds the points both segments cover to `points`
+    fn intersect(&self, other: &Segment, points: &mut HashSet<(i64, i64)>) {
+        let (rx, ry) = (other.x - self.x, other.y - self.y);
+        let cross = self.dx * other.dy - self.dy * other.dx;
+
+        if cross == 0 {
+            // Parallel, so they overlap only if on the same line
+            if other.dx != self.dx || other.dy != self.dy || rx * self.dy != ry * self.dx {
+                return;
             }
-        } else if p0.x + p0.y == p1.y + p1.x {
-            /* Descending diagonal */
-            let (x_min, x_max) = ascending(p0.x, p1.x);
-            let (y_min, y_max) = ascending(p0.y, p1.y);
-            for (x, y) in (x_min..=x_max).zip((y_min..=y_max).rev()) {
-                buf[x as usize + pitch * (y as usize)] += 1;
+            let start = if self.dx != 0 {
+                rx * self.dx
+            } else {
+                ry * self.dy
+            };
+            for step in start.max(0)..=(start + other.len).min(self.len) {
+                points.insert(self.at(step));
             }
+            return;
+        }
+
+        let s = rx * other.dy - ry * other.dx;
+        let t = rx * self.dy - ry * self.dx;
+        if s % cross != 0 || t % cross != 0 {
+            // Diagonals crossing between grid points
+            return;
+        }
+        let (s, t) = (s / cross, t / cross);
+        if (0..=self.len).contains(&s) && (0..=other.len).contains(&t) {
+            points.insert(self.at(s));
+        }
+    }
+}
+
+// Counts the overlaps without drawing the lines, by intersecting every pair
+fn analytic(lines: &[Line], classes: &[Class]) -> u32 {
+    let segments: Vec<Segment> = lines
+        .iter()
+        .filter(|l| classes.contains(&l.class()))
+        .filter_map(Segment::from_line)
+        .collect();
+    let mut points = HashSet::new();
+
+    for (n, a) in segments.iter().enumerate() {
+        for b in segments[n + 1..].iter() {
+            a.intersect(b, &mut points);
+        }
+    }
+
+    points.len() as u32
+}
+
+// The given classes, and lines of any other slope with `--any-slope`
+fn classes_with_args(classes: &[Class], args: &[String]) -> Vec<Class> {
+    let mut classes = classes.to_vec();
+    if opts::flag(args, "--any-slope") {
+        classes.push(Class::Other);
+    }
+    classes
+}
+
+fn run_with(input: &mut dyn Read, args: &[String], classes: &[Class]) {
+    let lines = load(input);
+    let classes = classes_with_args(classes, args);
+
+    let skipped = other_lines(&lines);
+    if opts::flag(args, "--strict") && !classes.contains(&Class::Other) && !skipped.is_empty() {
+        for n in skipped.iter() {
+            let Line(p0, p1) = &lines[n - 1];
+            eprintln!("skipped line {}: {},{} -> {},{}", n, p0.x, p0.y, p1.x, p1.y);
         }
+        eprintln!("{} lines with unsupported slopes", skipped.len());
+        std::process::exit(1);
     }
 
-    buf.into_iter().filter(|n| *n > 1).count() as u32
+    let overlaps = if opts::flag(args, "--analytic") {
+        if classes.contains(&Class::Other) {
+            opts::fail("--analytic only handles straight and diagonal lines");
+        }
+        analytic(&lines, &classes)
+    } else {
+        count_overlaps(&lines, &classes)
+    };
+    println!("{}", overlaps);
 }
 
-pub fn run_part1(input: &mut dyn Read) {
-    println!("{}", part1(input));
+pub fn run_part1(input: &mut dyn Read, args: &[String]) {
+    run_with(input, args, STRAIGHT);
 }
 
-pub fn run_part2(input: &mut dyn Read) {
-    println!("{}", part2(input));
+pub fn run_part2(input: &mut dyn Read, args: &[String]) {
+    run_with(input, args, WITH_DIAGONALS);
+}
+
+pub fn run_render(input: &mut dyn Read, args: &[String]) {
+    let map = draw_lines(&load(input), &classes_with_args(WITH_DIAGONALS, args));
+    let grid = match map.grid() {
+        Some(grid) => grid,
+        None => {
+            eprintln!("The vents are spread too far apart to render");
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = opts::value::<String>(args, "--pgm") {
+        export(&path, &grid, write_pgm);
+    }
+    if let Some(path) = opts::value::<String>(args, "--ppm") {
+        export(&path, &grid, write_ppm);
+    }
+
+    if grid.width <= TEXT_LIMIT && grid.height() <= TEXT_LIMIT {
+        print!("{}", grid.to_text());
+    } else {
+        println!(
+            "{} by {} map, too large to print, use --pgm or --ppm",
+            grid.width,
+            grid.height()
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::Rng;
     use std::fs::File;
 
+    fn part1(input: &mut dyn Read) -> u32 {
+        count_overlaps(&load(input), STRAIGHT)
+    }
+
+    fn part2(input: &mut dyn Read) -> u32 {
+        count_overlaps(&load(input), WITH_DIAGONALS)
+    }
+
     #[test]
     fn test_point() {
         let pt = Point::from_text("121,396");
@@ -141,6 +489,162 @@ mod tests {
         assert_eq!(p1.y, 892);
     }
 
+    #[test]
+    fn test_large_coordinates() {
+        let txt = "4294967295,0 -> 4294967293,0\n4294967294,2 -> 4294967294,0\n0,4294967295 -> 0,4294967295\n";
+        let lines = load(&mut txt.as_bytes());
+        assert!(matches!(VentMap::for_lines(&lines), VentMap::Sparse(_)));
+        assert_eq!(part1(&mut txt.as_bytes()), 1);
+
+        let txt = "5000,5000 -> 5002,5002\n5002,5000 -> 5000,5002\n";
+        let lines = load(&mut txt.as_bytes());
+        assert!(matches!(
+            VentMap::for_lines(&lines),
+            VentMap::Dense { width: 3, .. }
+        ));
+        assert_eq!(part2(&mut txt.as_bytes()), 1);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let seg = |txt: &str| Segment::from_line(&Line::from_text(txt)).unwrap();
+        let points = |a: &str, b: &str| {
+            let mut points = HashSet::new();
+            seg(a).intersect(&seg(b), &mut points);
+            let mut points: Vec<(i64, i64)> = points.into_iter().collect();
+            points.sort();
+            points
+        };
+
+        assert_eq!(points("0,0 -> 4,4", "4,0 -> 0,4"), vec![(2, 2)]);
+        assert_eq!(points("0,0 -> 3,3", "3,0 -> 0,3"), vec![]);
+        assert_eq!(points("0,2 -> 5,2", "3,7 -> 3,0"), vec![(3, 2)]);
+        assert_eq!(points("0,2 -> 5,2", "6,7 -> 6,0"), vec![]);
+        assert_eq!(
+            points("5,0 -> 0,0", "3,0 -> 9,0"),
+            vec![(3, 0), (4, 0), (5, 0)]
+        );
+        assert_eq!(points("0,0 -> 2,2", "4,4 -> 1,1"), vec![(1, 1), (2, 2)]);
+        assert_eq!(points("0,0 -> 2,2", "1,0 -> 3,2"), vec![]);
+        assert_eq!(points("3,3 -> 3,3", "0,6 -> 6,0"), vec![(3, 3)]);
+        assert!(Segment::from_line(&Line::from_text("0,0 -> 2,1")).is_none());
+    }
+
+    #[test]
+    fn test_analytic_matches_raster() {
+        for path in ["input/day-5-sample.txt", "input/day-5.txt"] {
+            let mut f = File::open(path).unwrap();
+            let lines = load(&mut f);
+            for classes in [STRAIGHT, WITH_DIAGONALS] {
+                assert_eq!(analytic(&lines, classes), count_overlaps(&lines, classes));
+            }
+        }
+
+        // Short lines on a small field, for plenty of collinear overlaps
+        let mut rng = Rng::with_seed(5);
+        let mut next = |n: usize| rng.below(n);
+        let mut txt = String::new();
+        for _ in 0..300 {
+            let (x, y, len) = (next(30) + 10, next(30) + 10, next(10));
+            let (x1, y1) = match next(4) {
+                0 => (x + len, y),
+                1 => (x, y + len),
+                2 => (x + len, y + len),
+                _ => (x + len, y - len),
+            };
+            txt += &format!("{},{} -> {},{}\n", x, y, x1, y1);
+        }
+        let lines = load(&mut txt.as_bytes());
+        for classes in [STRAIGHT, WITH_DIAGONALS] {
+            assert_eq!(analytic(&lines, classes), count_overlaps(&lines, classes));
+        }
+    }
+
+    #[test]
+    fn test_class() {
+        let class = |txt: &str| Line::from_text(txt).class();
+        assert_eq!(class("3,0 -> 3,7"), Class::Vertical);
+        assert_eq!(class("3,3 -> 3,3"), Class::Vertical);
+        assert_eq!(class("9,4 -> 3,4"), Class::Horizontal);
+        assert_eq!(class("1,1 -> 3,3"), Class::Ascending);
+        assert_eq!(class("7,9 -> 9,7"), Class::Descending);
+        assert_eq!(class("0,0 -> 4,2"), Class::Other);
+    }
+
+    #[test]
+    fn test_points() {
+        let points = |txt: &str| Line::from_text(txt).points().collect::<Vec<(u32, u32)>>();
+        assert_eq!(points("2,2 -> 2,2"), vec![(2, 2)]);
+        assert_eq!(points("0,0 -> 3,0"), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(points("3,0 -> 0,3"), vec![(3, 0), (2, 1), (1, 2), (0, 3)]);
+        assert_eq!(
+            points("0,0 -> 4,2"),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+        assert_eq!(points("1,5 -> 0,0").len(), 6);
+    }
+
+    #[test]
+    fn test_any_slope() {
+        let lines = load(&mut "0,0 -> 4,2\n0,1 -> 4,1\n2,0 -> 2,4\n".as_bytes());
+        let any_slope = [WITH_DIAGONALS, &[Class::Other]].concat();
+        assert_eq!(count_overlaps(&lines, WITH_DIAGONALS), 1);
+        assert_eq!(count_overlaps(&lines, &any_slope), 2);
+        assert_eq!(other_lines(&lines), vec![1]);
+
+        let mut f = File::open("input/day-5.txt").unwrap();
+        let lines = load(&mut f);
+        assert_eq!(count_overlaps(&lines, &any_slope), 22335);
+        assert_eq!(other_lines(&lines), vec![]);
+    }
+
+    #[test]
+    fn test_render_sample() {
+        let mut f = File::open("input/day-5-sample.txt").unwrap();
+        let map = draw_lines(&load(&mut f), WITH_DIAGONALS);
+        let grid = map.grid().unwrap();
+        assert_eq!(
+            grid.to_text(),
+            "1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+"
+        );
+
+        let lines = load(&mut "10,10 -> 12,10\n11,9 -> 11,11\n".as_bytes());
+        let map = draw_lines(&lines, WITH_DIAGONALS);
+        assert_eq!(
+            map.grid().unwrap().to_text(),
+            "top left at 10,9\n.1.\n121\n.1.\n"
+        );
+
+        let mut out = Vec::new();
+        write_pgm(&mut out, &grid).unwrap();
+        assert!(out.starts_with(b"P5\n10 10\n255\n"));
+        assert_eq!(out.len(), 13 + 100);
+        assert_eq!(out[13..].iter().max(), Some(&255));
+
+        let mut out = Vec::new();
+        write_ppm(&mut out, &grid).unwrap();
+        assert!(out.starts_with(b"P6\n10 10\n255\n"));
+        assert_eq!(out.len(), 13 + 300);
+        // The two triple overlaps are the only white pixels
+        assert_eq!(
+            out[13..]
+                .chunks(3)
+                .filter(|p| *p == [255, 255, 255])
+                .count(),
+            2
+        );
+    }
+
     #[test]
     fn test_part1_sample() {
         let mut f = File::open("input/day-5-sample.txt").unwrap();
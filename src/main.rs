@@ -2,67 +2,85 @@ use std::env::args;
 use std::io::stdin;
 use std::io::Read;
 
+mod day_1;
 mod day_10;
 mod day_11;
 mod day_12;
 mod day_13;
 mod day_14;
 mod day_15;
-mod day_1a;
-mod day_1b;
-mod day_2a;
-mod day_2b;
-mod day_3a;
-mod day_3b;
-mod day_4a;
-mod day_4b;
+mod day_2;
+mod day_21;
+mod day_22;
+mod day_23;
+mod day_24;
+mod day_25;
+mod day_3;
+mod day_4;
 mod day_5;
 mod day_6;
 mod day_7;
 mod day_8;
 mod day_9;
+mod opts;
+mod rng;
 
-const CMDS: &[(&str, fn(&mut dyn Read))] = &[
-    ("day-1a", day_1a::run),
-    ("day-1b", day_1b::run),
-    ("day-2a", day_2a::run),
-    ("day-2b", day_2b::run),
-    ("day-3a", day_3a::run),
-    ("day-3b", day_3b::run),
-    ("day-4a", day_4a::run),
-    ("day-4b", day_4b::run),
+// Every subcommand gets the options that follow its name. The ones
+// without options just ignore them.
+type Cmd = fn(&mut dyn Read, &[String]);
+
+const CMDS: &[(&str, Cmd)] = &[
+    ("day-1a", day_1::run_part1),
+    ("day-1b", day_1::run_part2),
+    ("day-1-report", |i, _| day_1::run_report(i)),
+    ("day-2a", day_2::run_part1),
+    ("day-2b", day_2::run_part2),
+    ("day-3a", day_3::run_part1),
+    ("day-3b", day_3::run_part2),
+    ("day-4a", day_4::run_part1),
+    ("day-4b", day_4::run_part2),
+    ("day-4-ranking", day_4::run_ranking),
+    ("day-4-odds", day_4::run_odds),
     ("day-5a", day_5::run_part1),
     ("day-5b", day_5::run_part2),
-    ("day-6a", day_6::run_part1),
-    ("day-6b", day_6::run_part2),
-    ("day-7a", day_7::run_part1),
-    ("day-7b", day_7::run_part2),
-    ("day-8a", day_8::run_part1),
-    ("day-8b", day_8::run_part2),
-    ("day-9a", day_9::run_part1),
-    ("day-9b", day_9::run_part2),
-    ("day-10a", day_10::run_part1),
-    ("day-10b", day_10::run_part2),
-    ("day-11a", day_11::run_part1),
-    ("day-11b", day_11::run_part2),
-    ("day-12a", day_12::run_part1),
-    ("day-12b", day_12::run_part2),
-    ("day-12-dot", day_12::run_to_dot),
-    ("day-13a", day_13::run_part1),
-    ("day-13b", day_13::run_part2),
-    ("day-14a", day_14::run_part1),
-    ("day-14b", day_14::run_part2),
-    ("day-15a", day_15::run_part1),
-    ("day-15b", day_15::run_part2),
+    ("day-5-render", day_5::run_render),
+    ("day-6a", |i, _| day_6::run_part1(i)),
+    ("day-6b", |i, _| day_6::run_part2(i)),
+    ("day-7a", |i, _| day_7::run_part1(i)),
+    ("day-7b", |i, _| day_7::run_part2(i)),
+    ("day-8a", |i, _| day_8::run_part1(i)),
+    ("day-8b", |i, _| day_8::run_part2(i)),
+    ("day-9a", |i, _| day_9::run_part1(i)),
+    ("day-9b", |i, _| day_9::run_part2(i)),
+    ("day-10a", |i, _| day_10::run_part1(i)),
+    ("day-10b", |i, _| day_10::run_part2(i)),
+    ("day-11a", |i, _| day_11::run_part1(i)),
+    ("day-11b", |i, _| day_11::run_part2(i)),
+    ("day-12a", |i, _| day_12::run_part1(i)),
+    ("day-12b", |i, _| day_12::run_part2(i)),
+    ("day-12-dot", |i, _| day_12::run_to_dot(i)),
+    ("day-13a", |i, _| day_13::run_part1(i)),
+    ("day-13b", |i, _| day_13::run_part2(i)),
+    ("day-14a", |i, _| day_14::run_part1(i)),
+    ("day-14b", |i, _| day_14::run_part2(i)),
+    ("day-15a", |i, _| day_15::run_part1(i)),
+    ("day-15b", |i, _| day_15::run_part2(i)),
+    ("day-21a", day_21::run_part1),
+    ("day-21b", day_21::run_part2),
+    ("day-22a", |i, _| day_22::run_part1(i)),
+    ("day-22b", |i, _| day_22::run_part2(i)),
+    ("day-23a", day_23::run_part1),
+    ("day-23b", day_23::run_part2),
+    ("day-24a", |i, _| day_24::run_part1(i)),
+    ("day-24b", |i, _| day_24::run_part2(i)),
+    ("day-24-run", day_24::run_program),
+    ("day-25", day_25::run),
 ];
 
-fn find_cmd(name: &str) -> Option<fn(&mut dyn Read)> {
-    for (fun_name, fun) in CMDS.iter() {
-        if name == *fun_name {
-            return Some(*fun);
-        }
-    }
-    return None;
+fn find_cmd(name: &str) -> Option<Cmd> {
+    CMDS.iter()
+        .find(|(fun_name, _)| name == *fun_name)
+        .map(|(_, fun)| *fun)
 }
 
 fn help(name: &str) {
@@ -74,7 +92,7 @@ fn help(name: &str) {
 }
 
 fn main() {
-    let (subcmd_name, prog_name) = {
+    let (subcmd_name, prog_name, opts) = {
         let mut args = args();
         let prog_name = args.next().unwrap();
 
@@ -85,7 +103,7 @@ fn main() {
             }
             Some(cmd) => cmd,
         };
-        (subcmd, prog_name)
+        (subcmd, prog_name, args.collect::<Vec<String>>())
     };
 
     let run_fn = match find_cmd(&subcmd_name) {
@@ -97,5 +115,5 @@ fn main() {
         }
     };
 
-    run_fn(&mut stdin())
+    run_fn(&mut stdin(), &opts)
 }
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+// Minimal helpers for the few subcommands that take options after the
+// subcommand name, e.g. `day-21b --board 10 --target 21`.
+
+pub fn flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
+
+pub fn value<T>(args: &[String], name: &str) -> Option<T>
+where
+    T: FromStr,
+{
+    let pos = args.iter().position(|a| a == name)?;
+    let text = match args.get(pos + 1) {
+        Some(t) => t,
+        None => fail(&format!("option {} needs a value", name)),
+    };
+
+    match text.parse() {
+        Ok(v) => Some(v),
+        Err(_) => fail(&format!("bad value {:?} for option {}", text, name)),
+    }
+}
+
+// For options that are missing their value, fail to parse or make no
+// sense, e.g. a board of size zero
+pub fn fail(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(2);
+}
+
+pub fn value_or<T>(args: &[String], name: &str, default: T) -> T
+where
+    T: FromStr,
+{
+    value(args, name).unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(txt: &str) -> Vec<String> {
+        txt.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_flag() {
+        assert!(flag(&args("--moves"), "--moves"));
+        assert!(!flag(&args("--board 3"), "--moves"));
+    }
+
+    #[test]
+    fn test_value() {
+        let a = args("--target 21 --faces 3");
+        assert_eq!(value::<u32>(&a, "--target"), Some(21));
+        assert_eq!(value::<u32>(&a, "--faces"), Some(3));
+        assert_eq!(value::<u32>(&a, "--other"), None);
+        assert_eq!(value_or(&a, "--other", 7u32), 7);
+    }
+}
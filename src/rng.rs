@@ -0,0 +1,53 @@
+// Linear congruential generator, plenty for shuffling puzzle input and
+// making up test data. Runs are repeatable for a given seed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn with_seed(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 32) as u32
+    }
+
+    // Uniform in 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u32() as u64 * n as u64) >> 32) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for n in (1..items.len()).rev() {
+            items.swap(n, self.below(n + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_below() {
+        let mut rng = Rng::with_seed(3);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            seen[rng.below(6)] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Rng::with_seed(1);
+        let mut nums: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut nums);
+        assert_ne!(nums, (0..100).collect::<Vec<u32>>());
+        nums.sort();
+        assert_eq!(nums, (0..100).collect::<Vec<u32>>());
+    }
+}